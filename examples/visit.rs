@@ -12,6 +12,6 @@ fn main() {
     let mut parts = Vec::new();
     address.visit_one(123456, |part| parts.push(part.to_string()));
 
-    // NB: `num` produces repeated digits, so '91' is really '9' and '1'
-    assert_eq!(parts, ["N", " ", "9", "1", " ", "Street"]);
+    // NB: `num` produces repeated digits, so '1691' is really '1', '6', '9', and '1'
+    assert_eq!(parts, ["N", " ", "1", "6", "9", "1", " ", "Place"]);
 }
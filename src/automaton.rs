@@ -0,0 +1,962 @@
+//! Set operations between the values of generators, through automata.
+//!
+//! Each generator is compiled to a nondeterministic automaton over ranges of characters, which is
+//! made deterministic and combined with the other generator's. A generator has finitely many
+//! values, so the combined automaton has no cycles, and since a deterministic automaton reads
+//! each string along a single path, indexing the paths through it gives each value exactly once.
+use crate::index::Index;
+use crate::int_range::sequence;
+use crate::{CharClass, Generator, SetOperationError};
+#[cfg(feature = "bigint")]
+use num_bigint::BigUint;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// A transition on the code points `lo..=hi` to the state `to`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Move {
+    lo: u32,
+    hi: u32,
+    to: usize,
+}
+
+#[derive(Debug, Default)]
+struct NfaState {
+    epsilon: Vec<usize>,
+    moves: Vec<Move>,
+}
+
+/// A nondeterministic automaton whose paths from [`START`](Self::START) to
+/// [`ACCEPT`](Self::ACCEPT) spell the values of a generator.
+#[derive(Debug, Default)]
+struct Nfa {
+    states: Vec<NfaState>,
+}
+
+impl Nfa {
+    const START: usize = 0;
+    const ACCEPT: usize = 1;
+
+    fn new(g: &Generator) -> Result<Self, SetOperationError> {
+        let mut nfa = Nfa::default();
+        let start = nfa.add_state();
+        let accept = nfa.add_state();
+        nfa.build(g, start, accept)?;
+        Ok(nfa)
+    }
+
+    fn add_state(&mut self) -> usize {
+        self.states.push(NfaState::default());
+        self.states.len() - 1
+    }
+
+    /// Adds a move on `range`, split around the surrogates so that every code point it spans is
+    /// a character.
+    fn add_move(&mut self, from: usize, range: RangeInclusive<char>, to: usize) {
+        let (lo, hi) = (*range.start() as u32, *range.end() as u32);
+        let pieces = if lo < 0xD800 && hi > 0xDFFF {
+            vec![(lo, 0xD7FF), (0xE000, hi)]
+        } else {
+            vec![(lo, hi)]
+        };
+        for (lo, hi) in pieces {
+            self.states[from].moves.push(Move { lo, hi, to });
+        }
+    }
+
+    /// Adds states so that the paths from `start` to `end` spell exactly the values of `g`.
+    ///
+    /// Nothing is added leading into `start` or out of `end`, so sub-automata can share them.
+    fn build(&mut self, g: &Generator, start: usize, end: usize) -> Result<(), SetOperationError> {
+        use Generator::*;
+        match g {
+            Char(c) => self.add_move(start, *c..=*c, end),
+            Str(s) => {
+                let chars: Vec<char> = s.chars().collect();
+                self.chain(start, end, chars.len(), |nfa, i, from, to| {
+                    nfa.add_move(from, chars[i]..=chars[i], to);
+                    Ok(())
+                })?;
+            }
+            CharClass(class) => class.ranges().for_each(|r| self.add_move(start, r, end)),
+            IntRange(range) => self.build(&range.to_generator(), start, end)?,
+            DateTime(_) => return Err(SetOperationError::DateTimeRange),
+            OneOf { v, is_optional } => {
+                if *is_optional {
+                    self.states[start].epsilon.push(end);
+                }
+                for a in v {
+                    self.build(a, start, end)?;
+                }
+            }
+            RepeatedN(a, n) => {
+                self.chain(start, end, *n, |nfa, _, from, to| nfa.build(a, from, to))?
+            }
+            RepeatedMN(a, m, n) => self.chain(start, end, *n, |nfa, i, from, to| {
+                // Stopping is allowed after the first m repetitions
+                if i >= *m {
+                    nfa.states[from].epsilon.push(end);
+                }
+                nfa.build(a, from, to)
+            })?,
+            Sequence(v) => self.chain(start, end, v.len(), |nfa, i, from, to| {
+                nfa.build(&v[i], from, to)
+            })?,
+            Transform {
+                inner,
+                transform_fn,
+            } => match transform_fn.builtin().and_then(|t| t.map_generator(inner)) {
+                Some(transformed) => self.build(&transformed, start, end)?,
+                None => return Err(SetOperationError::OpaqueTransform),
+            },
+            Filter(_) => return Err(SetOperationError::Filter),
+            Automaton(automaton) => automaton.embed(self, start, end),
+            Empty => self.states[start].epsilon.push(end),
+
+            // Built-in character classes
+            AlphaLower | AlphaUpper | Digit | AlphaNumLower | AlphaNumUpper | HexUpper
+            | HexLower | Base64 | Base64Url | Base32 | Base58 | PrintableAscii | Punctuation
+            | Whitespace => {
+                let alphabet = g.alphabet().expect("built-in classes have alphabets");
+                let class: crate::CharClass = alphabet.iter().map(|b| *b as char).collect();
+                class.ranges().for_each(|r| self.add_move(start, r, end));
+            }
+        }
+        Ok(())
+    }
+
+    /// Connects `start` to `end` through `n` steps, adding a state between each, where `step`
+    /// adds the `i`th step from `from` to `to`.
+    fn chain(
+        &mut self,
+        start: usize,
+        end: usize,
+        n: usize,
+        mut step: impl FnMut(&mut Self, usize, usize, usize) -> Result<(), SetOperationError>,
+    ) -> Result<(), SetOperationError> {
+        if n == 0 {
+            self.states[start].epsilon.push(end);
+            return Ok(());
+        }
+        let mut from = start;
+        for i in 0..n {
+            let to = if i + 1 == n { end } else { self.add_state() };
+            step(self, i, from, to)?;
+            from = to;
+        }
+        Ok(())
+    }
+
+    /// The states reachable from `states` without reading anything, in order.
+    fn closure(&self, mut states: Vec<usize>) -> Vec<usize> {
+        let mut stack = states.clone();
+        while let Some(s) = stack.pop() {
+            for t in &self.states[s].epsilon {
+                if !states.contains(t) {
+                    states.push(*t);
+                    stack.push(*t);
+                }
+            }
+        }
+        states.sort_unstable();
+        states.dedup();
+        states
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct DfaState {
+    accepting: bool,
+
+    /// Disjoint moves, in order
+    moves: Vec<Move>,
+}
+
+/// A deterministic automaton, starting from state 0.
+#[derive(Debug)]
+struct Dfa {
+    states: Vec<DfaState>,
+}
+
+/// The operations between the values of two generators
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operation {
+    Difference,
+    Intersection,
+}
+
+impl Dfa {
+    /// The subset construction: each state is the set of `nfa`'s states that a string can reach.
+    fn new(nfa: &Nfa) -> Self {
+        let mut sets = vec![nfa.closure(vec![Nfa::START])];
+        let mut ids = HashMap::from([(sets[0].clone(), 0)]);
+        let mut states = vec![];
+        while states.len() < sets.len() {
+            let set = &sets[states.len()];
+            let accepting = set.contains(&Nfa::ACCEPT);
+            let moves: Vec<Move> = set
+                .iter()
+                .flat_map(|s| nfa.states[*s].moves.iter().copied())
+                .collect();
+
+            let mut dfa_moves = vec![];
+            for (lo, hi, targets) in split(&moves) {
+                let target = nfa.closure(targets);
+                let to = *ids.entry(target.clone()).or_insert_with(|| {
+                    sets.push(target);
+                    sets.len() - 1
+                });
+                push_move(&mut dfa_moves, lo, hi, to);
+            }
+            states.push(DfaState {
+                accepting,
+                moves: dfa_moves,
+            });
+        }
+        Self { states }
+    }
+
+    /// The product of `a` and `b`, accepting the strings `a` accepts that `b` doesn't for a
+    /// difference, or that `b` also accepts for an intersection.
+    fn product(a: &Dfa, b: &Dfa, operation: Operation) -> Self {
+        // A state of `b` of `None` is one from which it accepts nothing
+        let mut pairs = vec![(0, Some(0))];
+        let mut ids = HashMap::from([((0, Some(0)), 0)]);
+        let mut states = vec![];
+        while states.len() < pairs.len() {
+            let (sa, sb) = pairs[states.len()];
+            let b_accepts = sb.is_some_and(|sb| b.states[sb].accepting);
+            let accepting = a.states[sa].accepting
+                && match operation {
+                    Operation::Difference => !b_accepts,
+                    Operation::Intersection => b_accepts,
+                };
+
+            // Split each of a's moves by the moves of b that overlap it
+            let b_moves = sb.map_or(&[][..], |sb| &b.states[sb].moves[..]);
+            let mut pieces = vec![];
+            for m in &a.states[sa].moves {
+                let mut lo = m.lo;
+                for n in b_moves.iter().filter(|n| n.hi >= m.lo && n.lo <= m.hi) {
+                    if n.lo > lo {
+                        pieces.push((lo, n.lo - 1, (m.to, None)));
+                    }
+                    pieces.push((lo.max(n.lo), m.hi.min(n.hi), (m.to, Some(n.to))));
+                    lo = n.hi + 1;
+                }
+                if lo <= m.hi {
+                    pieces.push((lo, m.hi, (m.to, None)));
+                }
+            }
+
+            let mut moves = vec![];
+            for (lo, hi, pair) in pieces {
+                if operation == Operation::Intersection && pair.1.is_none() {
+                    continue;
+                }
+                let to = *ids.entry(pair).or_insert_with(|| {
+                    pairs.push(pair);
+                    pairs.len() - 1
+                });
+                push_move(&mut moves, lo, hi, to);
+            }
+            states.push(DfaState { accepting, moves });
+        }
+        Self { states }
+    }
+}
+
+/// The states reachable from state 0, each after every state it moves to.
+fn post_order(states: &[DfaState]) -> Vec<usize> {
+    let mut order = vec![];
+    let mut visited = vec![false; states.len()];
+    visited[0] = true;
+
+    // Each state being visited, with the number of its moves followed so far
+    let mut stack = vec![(0, 0)];
+    while let Some(&(s, i)) = stack.last() {
+        match states[s].moves.get(i) {
+            Some(m) => {
+                stack.last_mut().expect("the stack isn't empty").1 += 1;
+                if !visited[m.to] {
+                    visited[m.to] = true;
+                    stack.push((m.to, 0));
+                }
+            }
+            None => {
+                order.push(s);
+                stack.pop();
+            }
+        }
+    }
+    order
+}
+
+/// Splits overlapping moves into disjoint ranges, in order, each with every state that a move on
+/// it leads to.
+fn split(moves: &[Move]) -> Vec<(u32, u32, Vec<usize>)> {
+    let mut bounds: Vec<u32> = moves.iter().flat_map(|m| [m.lo, m.hi + 1]).collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let mut pieces = vec![];
+    for w in bounds.windows(2) {
+        let (lo, hi) = (w[0], w[1] - 1);
+        let targets: Vec<usize> = moves
+            .iter()
+            .filter(|m| m.lo <= lo && hi <= m.hi)
+            .map(|m| m.to)
+            .collect();
+        if !targets.is_empty() {
+            pieces.push((lo, hi, targets));
+        }
+    }
+    pieces
+}
+
+/// Adds a move, merging it with the last one if they're adjacent and lead to the same state.
+fn push_move(moves: &mut Vec<Move>, lo: u32, hi: u32, to: usize) {
+    match moves.last_mut() {
+        Some(last) if last.to == to && last.hi + 1 == lo => last.hi = hi,
+        _ => moves.push(Move { lo, hi, to }),
+    }
+}
+
+/// The number of characters a move is made on. Moves never span the surrogates, so this is
+/// the number of code points.
+fn width(m: &Move) -> u32 {
+    m.hi - m.lo + 1
+}
+
+/// The character at a code point from a move, which never falls among the surrogates.
+fn char_at(code_point: u32) -> char {
+    char::from_u32(code_point).expect("moves only span characters")
+}
+
+/// `first` followed by `rest`, with runs of the same pattern written as a repetition.
+fn prepend(first: Generator, rest: Generator) -> Generator {
+    match first {
+        Generator::Empty => return rest,
+        Generator::Sequence(v) => {
+            return v
+                .into_iter()
+                .rev()
+                .fold(rest, |rest, part| prepend(part, rest))
+        }
+        _ => {}
+    }
+
+    let (head, tail) = match rest {
+        Generator::Empty => return first,
+        Generator::Sequence(mut v) => (v.remove(0), v),
+        rest => (rest, vec![]),
+    };
+    let repeated = |g| match g {
+        Generator::RepeatedN(a, n) => (*a, n),
+        g => (g, 1),
+    };
+    let ((first, m), (head, n)) = (repeated(first), repeated(head));
+    let parts = if first == head {
+        vec![Generator::RepeatedN(Box::new(first), m + n)]
+    } else {
+        let unrepeated = |g, n| match n {
+            1 => g,
+            n => Generator::RepeatedN(Box::new(g), n),
+        };
+        vec![unrepeated(first, m), unrepeated(head, n)]
+    };
+    sequence(parts.into_iter().chain(tail).collect())
+}
+
+/// The values accepted by a deterministic automaton over characters, as created by
+/// [`Generator::difference`] and [`Generator::intersection`].
+///
+/// The automaton has no cycles, and reads each value along a single path. The number of values
+/// accepted from each state is counted once, when the automaton is created; generating or
+/// parsing a value then only follows its path, choosing between the moves out of each state by
+/// those counts. This takes the same time however many values there are, even when many paths
+/// pass through the same states.
+///
+/// Values are in lexicographic order of their code points, and each comes before the values it's
+/// a prefix of.
+#[derive(Clone)]
+pub struct Automaton {
+    /// The states from which some value is accepted, starting from state 0, where every move
+    /// leads to a later state
+    states: Arc<[DfaState]>,
+
+    /// The number of values accepted from each state, if it fits in a `u128`
+    counts: Arc<[Option<u128>]>,
+
+    /// The number of values accepted from each state
+    #[cfg(feature = "bigint")]
+    big_counts: Arc<[BigUint]>,
+}
+
+impl Automaton {
+    /// Drops the states of `dfa` (other than the start) from which no value is accepted, and
+    /// counts the values accepted from the rest.
+    fn new(dfa: Dfa) -> Self {
+        let order = post_order(&dfa.states);
+        let mut live = vec![false; dfa.states.len()];
+        for &s in &order {
+            let state = &dfa.states[s];
+            live[s] = state.accepting || state.moves.iter().any(|m| live[m.to]);
+        }
+
+        // Number the remaining states so that every move leads to a later one
+        let kept: Vec<usize> = order
+            .into_iter()
+            .rev()
+            .filter(|s| *s == 0 || live[*s])
+            .collect();
+        let mut ids = vec![None; dfa.states.len()];
+        for (id, s) in kept.iter().enumerate() {
+            ids[*s] = Some(id);
+        }
+        let states = kept
+            .iter()
+            .map(|s| DfaState {
+                accepting: dfa.states[*s].accepting,
+                moves: dfa.states[*s]
+                    .moves
+                    .iter()
+                    .filter_map(|m| {
+                        Some(Move {
+                            to: ids[m.to]?,
+                            ..*m
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+        Self::from_states(states)
+    }
+
+    /// Counts the values accepted from each state, where every move leads to a later state.
+    fn from_states(states: Vec<DfaState>) -> Self {
+        let mut counts: Vec<Option<u128>> = vec![None; states.len()];
+        for (s, state) in states.iter().enumerate().rev() {
+            counts[s] = state
+                .moves
+                .iter()
+                .try_fold(state.accepting as u128, |count, m| {
+                    count.checked_add(counts[m.to]?.checked_mul(width(m).into())?)
+                });
+        }
+
+        #[cfg(feature = "bigint")]
+        let mut big_counts = vec![BigUint::default(); states.len()];
+        #[cfg(feature = "bigint")]
+        for (s, state) in states.iter().enumerate().rev() {
+            big_counts[s] = state
+                .moves
+                .iter()
+                .map(|m| &big_counts[m.to] * width(m))
+                .sum::<BigUint>()
+                + state.accepting as u8;
+        }
+
+        Self {
+            states: states.into(),
+            counts: counts.into(),
+            #[cfg(feature = "bigint")]
+            big_counts: big_counts.into(),
+        }
+    }
+
+    /// Checks that deserialized `states` are an automaton that [`new`](Self::new) could have
+    /// built: each state's moves are on characters, disjoint, in order, and lead to later states
+    /// from which some value is accepted.
+    #[cfg(feature = "serde")]
+    fn check(states: &[DfaState]) -> Result<(), &'static str> {
+        if states.is_empty() {
+            return Err("an automaton needs a start state");
+        }
+        for (s, state) in states.iter().enumerate() {
+            if s > 0 && !state.accepting && state.moves.is_empty() {
+                return Err("every state but the start must accept some value");
+            }
+            let mut next = 0;
+            for m in &state.moves {
+                if m.lo < next || m.lo > m.hi {
+                    return Err("the moves of a state must be disjoint and in order");
+                }
+                if m.hi > char::MAX as u32 || (m.lo <= 0xDFFF && m.hi >= 0xD800) {
+                    return Err("moves must be on characters, not surrogates");
+                }
+                if m.to <= s || m.to >= states.len() {
+                    return Err("moves must lead to a later state");
+                }
+                next = m.hi + 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// The number of values accepted from `state`, or `None` if that doesn't fit in `I`.
+    fn count<I: Index>(&self, state: usize) -> Option<I> {
+        match self.counts[state] {
+            Some(count) => Some(I::from_u128(count)),
+            // Parsing without indices doesn't need the actual count
+            None if !I::INDEXED => Some(I::zero()),
+            #[cfg(feature = "bigint")]
+            None => I::from_big(&self.big_counts[state]),
+            #[cfg(not(feature = "bigint"))]
+            None => None,
+        }
+    }
+
+    /// The number of values accepted, or `None` if that doesn't fit in `I`.
+    pub(crate) fn len<I: Index>(&self) -> Option<I> {
+        self.count(0)
+    }
+
+    /// The number of values accepted after `m` and the state it leads to, and the number
+    /// accepted after each of its characters.
+    ///
+    /// Every state's count is at most the automaton's, so these fit in `I` if that does.
+    fn block<I: Index>(&self, m: &Move) -> (I, I) {
+        let count: I = self.count(m.to).expect("bounded by the automaton's length");
+        let block = count
+            .checked_mul(&I::from_u128(width(m).into()))
+            .expect("bounded by the automaton's length");
+        (block, count)
+    }
+
+    /// Appends the value at `num`, which must be less than the number of values, to `result`.
+    pub(crate) fn write<I: Index>(&self, mut num: I, result: &mut String) {
+        let mut state = 0;
+        loop {
+            // Each value comes before those it's a prefix of
+            let s = &self.states[state];
+            if s.accepting {
+                if num.is_zero() {
+                    return;
+                }
+                num = num.sub(&I::from_u128(1));
+            }
+
+            let mut next = None;
+            for m in &s.moves {
+                let (block, count) = self.block::<I>(m);
+                if num < block {
+                    let (offset, rest) = num.div_rem(&count);
+                    let offset = offset.to_u128().expect("less than the move's width") as u32;
+                    result.push(char_at(m.lo + offset));
+                    num = rest;
+                    next = Some(m.to);
+                    break;
+                }
+                num = num.sub(&block);
+            }
+            state = next.expect("num is less than the number of values accepted from the state");
+        }
+    }
+
+    /// Parses each prefix of `s` that's a value, returning its length in bytes and its index.
+    pub(crate) fn parse_prefixes<I: Index>(&self, s: &str) -> Vec<(usize, I)> {
+        let add = |a: &I, b: &I| a.checked_add(b).expect("bounded by the automaton's length");
+        let mut matches = vec![];
+        let mut state = 0;
+        let mut index = I::zero();
+        let mut chars = s.char_indices();
+        loop {
+            let (pos, c) = match chars.next() {
+                Some((pos, c)) => (pos, Some(c as u32)),
+                None => (s.len(), None),
+            };
+            if self.states[state].accepting {
+                matches.push((pos, index.clone()));
+                index = add(&index, &I::from_u128(1));
+            }
+            let Some(c) = c else {
+                return matches;
+            };
+
+            // Skip the values that branch off with earlier characters
+            let mut next = None;
+            for m in &self.states[state].moves {
+                let (block, count) = self.block::<I>(m);
+                if c > m.hi {
+                    index = add(&index, &block);
+                    continue;
+                }
+                if c >= m.lo {
+                    let offset = I::from_u128((c - m.lo).into());
+                    index = add(
+                        &index,
+                        &offset.checked_mul(&count).expect("bounded by block"),
+                    );
+                    next = Some(m.to);
+                }
+                break;
+            }
+            match next {
+                Some(to) => state = to,
+                None => return matches,
+            }
+        }
+    }
+
+    /// Adds states to `nfa` so that its paths from `start` to `end` spell this automaton's values.
+    fn embed(&self, nfa: &mut Nfa, start: usize, end: usize) {
+        let ids: Vec<usize> = self.states.iter().map(|_| nfa.add_state()).collect();
+        nfa.states[start].epsilon.push(ids[0]);
+        for (state, id) in self.states.iter().zip(&ids) {
+            if state.accepting {
+                nfa.states[*id].epsilon.push(end);
+            }
+            for m in &state.moves {
+                nfa.states[*id].moves.push(Move {
+                    to: ids[m.to],
+                    ..*m
+                });
+            }
+        }
+    }
+
+    /// A generator of the same values, though not in the same order, for rendering as a regex.
+    ///
+    /// Where every path from a state passes through another, the values in between are written
+    /// once and followed by those after it, so that paths that meet again aren't written out
+    /// separately.
+    pub(crate) fn to_generator(&self) -> Generator {
+        if self.counts[0] == Some(0) {
+            return Generator::OneOf {
+                v: vec![],
+                is_optional: false,
+            };
+        }
+        let ipdom = self.post_dominators();
+        self.segment(0, self.states.len(), &ipdom, &mut HashMap::new())
+    }
+
+    /// The immediate post-dominator of each state: the first state that every path from it to
+    /// the end of a value passes through, or `states.len()` for the end itself.
+    fn post_dominators(&self) -> Vec<usize> {
+        let end = self.states.len();
+        let mut ipdom = vec![end; end + 1];
+        let mut depth = vec![0; end + 1];
+        for s in post_order(&self.states) {
+            let state = &self.states[s];
+            let d = state
+                .moves
+                .iter()
+                .map(|m| m.to)
+                .chain(state.accepting.then_some(end))
+                .reduce(|mut a, mut b| {
+                    // The nearest common ancestor in the tree of post-dominators
+                    while a != b {
+                        if depth[a] >= depth[b] {
+                            a = ipdom[a];
+                        } else {
+                            b = ipdom[b];
+                        }
+                    }
+                    a
+                })
+                .expect("every state but an empty start accepts something");
+            ipdom[s] = d;
+            depth[s] = depth[d] + 1;
+        }
+        ipdom
+    }
+
+    /// A generator of the strings spelled from `state` to `stop`, which every path from `state`
+    /// to the end of a value passes through, or to the end of a value if `stop` is
+    /// `states.len()`.
+    fn segment(
+        &self,
+        state: usize,
+        stop: usize,
+        ipdom: &[usize],
+        memo: &mut HashMap<(usize, usize), Generator>,
+    ) -> Generator {
+        if state == stop {
+            return Generator::Empty;
+        }
+        if let Some(g) = memo.get(&(state, stop)) {
+            return g.clone();
+        }
+
+        let via = ipdom[state];
+        let g = if via != stop {
+            let first = self.segment(state, via, ipdom, memo);
+            prepend(first, self.segment(via, stop, ipdom, memo))
+        } else {
+            // Group the moves by the state they lead to, in order of their first characters
+            let mut targets: Vec<(usize, Vec<RangeInclusive<char>>)> = vec![];
+            for m in &self.states[state].moves {
+                let range = char_at(m.lo)..=char_at(m.hi);
+                match targets.iter_mut().find(|(to, _)| *to == m.to) {
+                    Some((_, ranges)) => ranges.push(range),
+                    None => targets.push((m.to, vec![range])),
+                }
+            }
+
+            let mut branches = vec![];
+            for (to, ranges) in targets {
+                let class: CharClass = ranges.into_iter().collect();
+                let first = match class.len() {
+                    1 => Generator::Char(class.get(0).expect("the class has a character")),
+                    _ => Generator::CharClass(class),
+                };
+                branches.push(prepend(first, self.segment(to, stop, ipdom, memo)));
+            }
+
+            // Only the end of a value can be reached without a move
+            match (branches.len(), self.states[state].accepting) {
+                (0, _) => Generator::Empty,
+                (1, false) => branches.remove(0),
+                (_, is_optional) => Generator::OneOf {
+                    v: branches,
+                    is_optional,
+                },
+            }
+        };
+        memo.insert((state, stop), g.clone());
+        g
+    }
+}
+
+impl std::fmt::Debug for Automaton {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Automaton")
+            .field("states", &self.states.len())
+            .field("len", &self.counts[0])
+            .finish_non_exhaustive()
+    }
+}
+
+/// An automaton is serialized as its states, each with whether it accepts and its moves.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Automaton {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.states.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Automaton {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let states: Vec<DfaState> = serde::Deserialize::deserialize(deserializer)?;
+        Self::check(&states).map_err(serde::de::Error::custom)?;
+        Ok(Self::from_states(states))
+    }
+}
+
+/// Automata are equal if they have the same states, and so the same values in the same order.
+impl PartialEq for Automaton {
+    fn eq(&self, other: &Self) -> bool {
+        self.states == other.states
+    }
+}
+
+impl From<Automaton> for Generator {
+    fn from(automaton: Automaton) -> Self {
+        Generator::Automaton(automaton)
+    }
+}
+
+fn set_operation(
+    a: &Generator,
+    b: &Generator,
+    operation: Operation,
+) -> Result<Generator, SetOperationError> {
+    let a = Dfa::new(&Nfa::new(a)?);
+    let b = Dfa::new(&Nfa::new(b)?);
+    let product = Dfa::product(&a, &b, operation);
+    Ok(Generator::Automaton(Automaton::new(product)))
+}
+
+impl Generator {
+    /// A generator of the values of this generator that `other` can't produce, each exactly once.
+    ///
+    /// The result is computed from the structure of both generators rather than by enumerating
+    /// their values: the result is an [`Automaton`](Self::Automaton), whose size depends on the
+    /// structure of the generators rather than on how many values they have. `len` is exact, and
+    /// `generate_one` and `index_of` map between the values and `0..len` one-to-one, even if this
+    /// generator produces some values more than once. The values are in lexicographic order of
+    /// their code points, rather than in this generator's order.
+    ///
+    /// Panics if either generator contains a [`Transform`](Self::Transform) that isn't a
+    /// [`BuiltinTransform`](crate::BuiltinTransform) prefix, suffix, reversal, or case change, a
+    /// [`DateTime`](Self::DateTime) range, or a [`Filter`](Self::Filter); see
+    /// [`try_difference`](Self::try_difference).
+    ///
+    /// ```
+    /// use generator_combinator::{oneof, Generator};
+    /// let identifiers = Generator::AlphaLower * 3;
+    /// let keywords = oneof!("and", "end", "for", "not", "set");
+    /// let g = identifiers.difference(&keywords);
+    /// assert_eq!(g.len(), 26 * 26 * 26 - 5);
+    /// assert!(!g.matches("for"));
+    ///
+    /// let i = g.index_of("foo").unwrap();
+    /// assert_eq!(g.generate_one(i), "foo");
+    /// ```
+    pub fn difference(&self, other: &Generator) -> Generator {
+        match self.try_difference(other) {
+            Ok(g) => g,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Like [`difference`](Self::difference), but returns a [`SetOperationError`] rather than
+    /// panicking if either generator can't be compared structurally.
+    pub fn try_difference(&self, other: &Generator) -> Result<Generator, SetOperationError> {
+        set_operation(self, other, Operation::Difference)
+    }
+
+    /// A generator of the values that both this generator and `other` can produce, each exactly
+    /// once.
+    ///
+    /// As with [`difference`](Self::difference), the result is computed structurally, `len` is
+    /// exact, and indices map to values one-to-one.
+    ///
+    /// Panics if either generator can't be compared structurally; see
+    /// [`try_intersection`](Self::try_intersection).
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// // Hex strings that are also decimal numbers
+    /// let g = (Generator::HexLower * (2, 4)).intersection(&(Generator::Digit * (1, 3)));
+    /// assert_eq!(g.len(), 100 + 1000);
+    /// assert_eq!(g.regex(), "[0-9]{2}[0-9]?");
+    /// ```
+    pub fn intersection(&self, other: &Generator) -> Generator {
+        match self.try_intersection(other) {
+            Ok(g) => g,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Like [`intersection`](Self::intersection), but returns a [`SetOperationError`] rather than
+    /// panicking if either generator can't be compared structurally.
+    pub fn try_intersection(&self, other: &Generator) -> Result<Generator, SetOperationError> {
+        set_operation(self, other, Operation::Intersection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{gen, oneof, BuiltinTransform, Generator, SetOperationError};
+    use std::collections::BTreeSet;
+
+    fn values(g: &Generator) -> BTreeSet<String> {
+        g.generate_all().collect()
+    }
+
+    /// Checks that `g` produces exactly `expected`, each once and in order, and maps them back
+    /// to their indices.
+    fn assert_produces(g: &Generator, expected: &BTreeSet<String>) {
+        assert_eq!(g.len(), expected.len() as u128, "{g:?}");
+        let produced: Vec<String> = g.generate_all().collect();
+        assert_eq!(produced, expected.iter().cloned().collect::<Vec<_>>());
+        for (i, s) in produced.iter().enumerate() {
+            assert_eq!(g.indices_of(s), vec![i as u128]);
+        }
+    }
+
+    #[test]
+    fn operations() {
+        let pairs = [
+            (
+                Generator::AlphaLower * (1, 2),
+                oneof!("a", "is", "zz", "abc"),
+            ),
+            (oneof!("a", "aa") * (1, 3), gen!('a') * (2, 4)),
+            (Generator::HexUpper * 2, Generator::int_range(0..=99)),
+            (
+                Generator::class("a-cé") + Generator::Digit.optional(),
+                Generator::Base64.transform(BuiltinTransform::Prefix("b".into())),
+            ),
+            (
+                Generator::int_range(-20..=20),
+                Generator::int_range(-5..=50),
+            ),
+            (
+                Generator::AlphaLower.transform(BuiltinTransform::Uppercase) * 2,
+                Generator::AlphaNumUpper + Generator::Empty,
+            ),
+        ];
+        for (a, b) in pairs {
+            let (va, vb) = (values(&a), values(&b));
+            assert_produces(&a.difference(&b), &(&va - &vb));
+            assert_produces(&a.intersection(&b), &(&va & &vb));
+            assert_produces(&b.difference(&a), &(&vb - &va));
+        }
+    }
+
+    #[test]
+    fn structure() {
+        // Runs of the same class are written as repetitions
+        let g = (Generator::AlphaLower * 3).difference(&oneof!("and", "not"));
+        assert_eq!(
+            g.regex(),
+            "a([a-mo-z][a-z]|n[a-ce-z])|[b-mo-z][a-z]{2}|n([a-np-z][a-z]|o[a-su-z])"
+        );
+
+        // Nothing is left, or nothing is shared
+        let g = Generator::Digit * 2;
+        assert!(g.difference(&g).is_empty());
+        assert!(g.intersection(&Generator::AlphaLower).is_empty());
+
+        // Large generators don't need to be enumerated
+        let g = (Generator::AlphaNumLower * 40).difference(&(Generator::Digit * 40));
+        assert!(g.try_len().is_err());
+        assert!(g.matches(&format!("{}a", "0".repeat(39))));
+        assert!(!g.matches(&"0".repeat(40)));
+        #[cfg(feature = "bigint")]
+        {
+            use crate::BigUint;
+            assert_eq!(
+                g.len_big(),
+                BigUint::from(36u8).pow(40) - BigUint::from(10u8).pow(40)
+            );
+            let i = g.len_big() - 1u8;
+            assert_eq!(g.generate_one_big(&i), "z".repeat(40));
+            assert_eq!(g.index_of_big(&"z".repeat(40)), Some(i));
+        }
+    }
+
+    #[test]
+    fn reconvergent() {
+        // Every choice of `ab` or `ba` meets again after it, so the automaton stays small even
+        // though there are 2^40 paths through it
+        let g = (oneof!("ab", "ba") * 40).intersection(&(Generator::AlphaLower * 80));
+        assert_eq!(g.len(), 1 << 40);
+        assert_eq!(g.regex(), "(ab|ba){40}");
+
+        let last = (1 << 40) - 1;
+        assert_eq!(g.generate_one(0), "ab".repeat(40));
+        assert_eq!(g.generate_one(last), "ba".repeat(40));
+        for i in [1, 12_345_678_901, last - 1] {
+            let s = g.generate_one(i);
+            assert!(g.matches(&s));
+            assert_eq!(g.index_of(&s), Some(i));
+        }
+        assert!(!g.matches(&"ab".repeat(39)));
+    }
+
+    #[test]
+    fn unsupported() {
+        let digits = Generator::Digit * 3;
+        let trimmed = digits
+            .clone()
+            .transform(|s: String| s.trim_start_matches('0').to_string());
+        assert_eq!(
+            digits.try_difference(&trimmed),
+            Err(SetOperationError::OpaqueTransform)
+        );
+        let filtered = digits.clone().filter(|s| s != "000");
+        assert_eq!(
+            filtered.try_intersection(&digits),
+            Err(SetOperationError::Filter)
+        );
+    }
+}
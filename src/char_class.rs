@@ -0,0 +1,570 @@
+//! Arbitrary sets of characters, for use as [`Generator::CharClass`].
+use crate::error::CharClassError;
+use crate::Generator;
+use std::ops::RangeInclusive;
+use std::str::{Chars, FromStr};
+
+/// The surrogate code points, which aren't valid `char`s.
+const SURROGATES: RangeInclusive<u32> = 0xD800..=0xDFFF;
+
+/// A set of characters, generated in code point order.
+///
+/// A class is stored as sorted, disjoint ranges of characters, so that finding the character of
+/// a given rank (or the rank of a given character) takes time logarithmic in the number of
+/// ranges, no matter how many characters they hold.
+///
+/// ```
+/// use generator_combinator::{CharClass, Generator};
+/// let class: CharClass = "a-fxz".parse().unwrap();
+/// assert_eq!(class.len(), 8);
+/// assert_eq!(class.get(6), Some('x'));
+/// assert_eq!(class.rank('x'), Some(6));
+///
+/// let g = Generator::from(class);
+/// assert_eq!(g.regex(), "[a-fxz]");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CharClass {
+    /// Sorted, non-adjacent ranges of code points, none of which includes a surrogate
+    ranges: Vec<RangeInclusive<u32>>,
+
+    /// The number of characters in the ranges before each range
+    offsets: Vec<u32>,
+
+    /// The Unicode property whose characters are exactly those of this class, if it was built
+    /// from one
+    #[cfg(feature = "unicode")]
+    property: Option<Property>,
+}
+
+/// A Unicode property, which a regex can match as `\p{...}`.
+#[cfg(feature = "unicode")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Property {
+    /// A general category such as `Lu` or `Letter`
+    GeneralCategory(String),
+
+    /// A script such as `Cyrillic` or `Han`
+    Script(String),
+
+    /// A binary property such as `Emoji` or `White_Space`
+    Binary(String),
+}
+
+/// Classes are equal if they have the same characters, however they were built.
+impl PartialEq for CharClass {
+    fn eq(&self, other: &Self) -> bool {
+        self.ranges == other.ranges
+    }
+}
+
+impl Eq for CharClass {}
+
+impl CharClass {
+    /// A class of no characters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a class from ranges of code points in any order, which may overlap.
+    fn from_code_points(ranges: impl IntoIterator<Item = RangeInclusive<u32>>) -> Self {
+        let mut ranges: Vec<_> = ranges.into_iter().filter(|r| !r.is_empty()).collect();
+        ranges.sort_by_key(|r| *r.start());
+
+        // Merge overlapping and adjacent ranges, then split any that span the surrogates
+        let mut merged: Vec<RangeInclusive<u32>> = vec![];
+        for r in ranges {
+            match merged.last_mut() {
+                Some(last) if *r.start() <= last.end() + 1 => {
+                    *last = *last.start()..=*last.end().max(r.end());
+                }
+                _ => merged.push(r),
+            }
+        }
+        let mut split = Vec::with_capacity(merged.len());
+        for r in merged {
+            if *r.start() < *SURROGATES.start() && *r.end() > *SURROGATES.end() {
+                split.push(*r.start()..=*SURROGATES.start() - 1);
+                split.push(*SURROGATES.end() + 1..=*r.end());
+            } else {
+                split.push(r);
+            }
+        }
+
+        let mut offsets = Vec::with_capacity(split.len());
+        let mut len = 0;
+        for r in &split {
+            offsets.push(len);
+            len += r.end() - r.start() + 1;
+        }
+
+        Self {
+            ranges: split,
+            offsets,
+            #[cfg(feature = "unicode")]
+            property: None,
+        }
+    }
+
+    /// The Unicode property this class was built from, if any.
+    #[cfg(feature = "unicode")]
+    pub(crate) fn property(&self) -> Option<&Property> {
+        self.property.as_ref()
+    }
+
+    /// The number of characters in this class.
+    pub fn len(&self) -> usize {
+        match (self.ranges.last(), self.offsets.last()) {
+            (Some(r), Some(offset)) => (offset + r.end() - r.start() + 1) as usize,
+            _ => 0,
+        }
+    }
+
+    /// Whether this class has no characters.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Whether `c` is in this class.
+    pub fn contains(&self, c: char) -> bool {
+        self.rank(c).is_some()
+    }
+
+    /// The character at position `rank` in code point order, or `None` if `rank` isn't less than
+    /// [`len`](Self::len).
+    pub fn get(&self, rank: usize) -> Option<char> {
+        let rank = u32::try_from(rank).ok()?;
+        let i = self.offsets.partition_point(|offset| *offset <= rank);
+        let r = self.ranges.get(i.checked_sub(1)?)?;
+        let c = r.start() + (rank - self.offsets[i - 1]);
+        if c > *r.end() {
+            return None;
+        }
+        char::from_u32(c)
+    }
+
+    /// The position of `c` in code point order, or `None` if it isn't in this class.
+    pub fn rank(&self, c: char) -> Option<usize> {
+        let c = c as u32;
+        let i = self.ranges.partition_point(|r| *r.end() < c);
+        let r = self.ranges.get(i)?;
+        if r.contains(&c) {
+            Some((self.offsets[i] + c - r.start()) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// The ranges of characters in this class, in order.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<char>> + '_ {
+        self.ranges.iter().map(|r| {
+            let start = char::from_u32(*r.start()).expect("ranges exclude surrogates");
+            let end = char::from_u32(*r.end()).expect("ranges exclude surrogates");
+            start..=end
+        })
+    }
+
+    /// The characters in either this class or `other`.
+    pub fn union(&self, other: &CharClass) -> CharClass {
+        Self::from_code_points(self.ranges.iter().chain(&other.ranges).cloned())
+    }
+
+    /// The characters in this class that aren't in `other`.
+    pub fn difference(&self, other: &CharClass) -> CharClass {
+        let mut remaining = vec![];
+        let mut excluded = other.ranges.iter().peekable();
+        for r in &self.ranges {
+            let mut start = *r.start();
+            let end = *r.end();
+            while let Some(x) = excluded.peek() {
+                if *x.end() < start {
+                    excluded.next();
+                    continue;
+                }
+                if *x.start() > end {
+                    break;
+                }
+                if *x.start() > start {
+                    remaining.push(start..=x.start() - 1);
+                }
+                if *x.end() >= end {
+                    start = end + 1;
+                    break;
+                }
+                start = x.end() + 1;
+                excluded.next();
+            }
+            if start <= end {
+                remaining.push(start..=end);
+            }
+        }
+        Self::from_code_points(remaining)
+    }
+
+    /// The characters of `base` that aren't in this class, like a negated class `[^...]` whose
+    /// alphabet is `base`.
+    ///
+    /// ```
+    /// use generator_combinator::CharClass;
+    /// let vowels: CharClass = "aeiou".parse().unwrap();
+    /// let consonants = vowels.negate(&CharClass::from('a'..='z'));
+    /// assert_eq!(consonants.len(), 21);
+    /// assert!(!consonants.contains('e'));
+    /// ```
+    pub fn negate(&self, base: &CharClass) -> CharClass {
+        base.difference(self)
+    }
+}
+
+/// Classes of the characters with a given Unicode property, using the tables of the Unicode
+/// version supported by the [`regex`](https://docs.rs/regex) crate.
+///
+/// These never include surrogates, and only [`general_category`](Self::general_category)`("Cn")`
+/// includes unassigned code points. Their regexes use `\p{...}`, except for POSIX EREs.
+///
+/// ```
+/// use generator_combinator::{CharClass, Generator};
+/// let cyrillic = CharClass::script("Cyrillic").unwrap();
+/// assert!(cyrillic.contains('ж'));
+///
+/// let g = Generator::from(cyrillic) * 2;
+/// assert_eq!(g.regex(), r"\p{Cyrillic}{2}");
+/// ```
+#[cfg(feature = "unicode")]
+impl CharClass {
+    /// The characters in the general category `name`, such as `Lu` or `Uppercase_Letter` for
+    /// uppercase letters, or `M` for all combining marks.
+    pub fn general_category(name: &str) -> Result<Self, CharClassError> {
+        Self::from_property(Property::GeneralCategory(name.into()))
+    }
+
+    /// The characters of the script `name`, such as `Cyrillic`, `Han`, or `Arabic`.
+    pub fn script(name: &str) -> Result<Self, CharClassError> {
+        Self::from_property(Property::Script(name.into()))
+    }
+
+    /// The characters with the binary property `name`, such as `Emoji` or `White_Space`.
+    pub fn binary_property(name: &str) -> Result<Self, CharClassError> {
+        Self::from_property(Property::Binary(name.into()))
+    }
+
+    /// The characters of this class that are assigned in Unicode, without those in the
+    /// general category `Cn`.
+    ///
+    /// ```
+    /// use generator_combinator::CharClass;
+    /// let arrows = CharClass::from('\u{1F800}'..='\u{1F8FF}');
+    /// assert_eq!(arrows.len(), 256);
+    /// assert!(arrows.assigned().len() < 256);
+    /// ```
+    pub fn assigned(&self) -> Self {
+        let unassigned = Self::general_category("Cn").expect("Cn is a general category");
+        self.difference(&unassigned)
+    }
+
+    fn from_property(property: Property) -> Result<Self, CharClassError> {
+        use regex_syntax::hir::{Class, HirKind};
+
+        let (pattern, name) = match &property {
+            Property::GeneralCategory(name) => (format!("\\p{{gc={name}}}"), name),
+            Property::Script(name) => (format!("\\p{{sc={name}}}"), name),
+            Property::Binary(name) => (format!("\\p{{{name}}}"), name),
+        };
+        let unknown = || CharClassError::UnknownProperty(name.clone());
+
+        // Keep the name from changing the meaning of the pattern or the regexes rendered from it
+        let valid_name = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ' ');
+        if name.is_empty() || !name.chars().all(valid_name) {
+            return Err(unknown());
+        }
+
+        let hir = regex_syntax::Parser::new()
+            .parse(&pattern)
+            .map_err(|_| unknown())?;
+        let HirKind::Class(Class::Unicode(class)) = hir.kind() else {
+            return Err(unknown());
+        };
+
+        let ranges = class
+            .ranges()
+            .iter()
+            .map(|r| r.start() as u32..=r.end() as u32);
+        Ok(Self {
+            property: Some(property),
+            ..Self::from_code_points(ranges)
+        })
+    }
+}
+
+/// Parses a class from the contents of a bracketed regex class, such as `a-fxz`.
+///
+/// A `-` between two characters forms an inclusive range, and a `\` makes the following character
+/// literal. A `-` at the start or end of the class is literal.
+impl FromStr for CharClass {
+    type Err = CharClassError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut chars = spec.chars();
+        let mut ranges = vec![];
+        while let Some(start) = next_literal(&mut chars) {
+            let start = start?;
+            let mut lookahead = chars.clone();
+            if lookahead.next() == Some('-') && lookahead.next().is_some() {
+                chars.next();
+                let end = next_literal(&mut chars).expect("checked by the lookahead")?;
+                if start > end {
+                    return Err(CharClassError::ReversedRange { start, end });
+                }
+                ranges.push(start as u32..=end as u32);
+            } else {
+                ranges.push(start as u32..=start as u32);
+            }
+        }
+        Ok(Self::from_code_points(ranges))
+    }
+}
+
+/// The next character of a class's spec, after any escape, or `None` at the end of the spec.
+fn next_literal(chars: &mut Chars) -> Option<Result<char, CharClassError>> {
+    match chars.next()? {
+        '\\' => Some(chars.next().ok_or(CharClassError::TrailingEscape)),
+        c => Some(Ok(c)),
+    }
+}
+
+impl From<RangeInclusive<char>> for CharClass {
+    fn from(r: RangeInclusive<char>) -> Self {
+        Self::from_code_points([*r.start() as u32..=*r.end() as u32])
+    }
+}
+
+impl FromIterator<char> for CharClass {
+    fn from_iter<T: IntoIterator<Item = char>>(iter: T) -> Self {
+        Self::from_code_points(iter.into_iter().map(|c| c as u32..=c as u32))
+    }
+}
+
+impl FromIterator<RangeInclusive<char>> for CharClass {
+    fn from_iter<T: IntoIterator<Item = RangeInclusive<char>>>(iter: T) -> Self {
+        Self::from_code_points(
+            iter.into_iter()
+                .map(|r| *r.start() as u32..=*r.end() as u32),
+        )
+    }
+}
+
+/// A class is serialized as its ranges of characters, so one built from a Unicode property
+/// renders as those ranges rather than as `\p{...}` once deserialized.
+#[cfg(feature = "serde")]
+impl serde::Serialize for CharClass {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.ranges())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CharClass {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ranges: Vec<RangeInclusive<char>> = serde::Deserialize::deserialize(deserializer)?;
+        Ok(ranges.into_iter().collect())
+    }
+}
+
+impl Generator {
+    /// A [`CharClass`] generator from the contents of a bracketed regex class, such as `a-fxz`
+    /// for the characters `a` through `f`, `x`, and `z`.
+    ///
+    /// Panics if `spec` isn't a valid class; see [`CharClass`]'s [`FromStr`] implementation for
+    /// a fallible version.
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// let g = Generator::class("a-fxz") * 2;
+    /// assert_eq!(g.len(), 64);
+    /// assert_eq!(g.generate_one(63), "zz");
+    /// ```
+    pub fn class(spec: &str) -> Self {
+        match spec.parse() {
+            Ok(class) => Generator::CharClass(class),
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
+
+#[cfg(feature = "unicode")]
+impl Generator {
+    /// A [`CharClass`] generator of the characters in a Unicode general category. See
+    /// [`CharClass::general_category`].
+    ///
+    /// Panics if `name` isn't a general category.
+    pub fn general_category(name: &str) -> Self {
+        match CharClass::general_category(name) {
+            Ok(class) => Generator::CharClass(class),
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// A [`CharClass`] generator of the characters of a Unicode script. See
+    /// [`CharClass::script`].
+    ///
+    /// Panics if `name` isn't a script.
+    pub fn script(name: &str) -> Self {
+        match CharClass::script(name) {
+            Ok(class) => Generator::CharClass(class),
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
+
+impl From<CharClass> for Generator {
+    fn from(class: CharClass) -> Self {
+        Generator::CharClass(class)
+    }
+}
+
+impl From<RangeInclusive<char>> for Generator {
+    fn from(r: RangeInclusive<char>) -> Self {
+        Generator::CharClass(r.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_spec() {
+        let class: CharClass = "a-fxz".parse().unwrap();
+        assert_eq!(
+            class.ranges().collect::<Vec<_>>(),
+            ['a'..='f', 'x'..='x', 'z'..='z']
+        );
+
+        // Ranges are merged and sorted, whatever order they're given in
+        let class: CharClass = "x-zd-ga-e".parse().unwrap();
+        assert_eq!(class.ranges().collect::<Vec<_>>(), ['a'..='g', 'x'..='z']);
+
+        let chars = |spec: &str| {
+            let class: CharClass = spec.parse().unwrap();
+            (0..class.len())
+                .map(|i| class.get(i).unwrap())
+                .collect::<String>()
+        };
+        assert_eq!(chars("-a"), "-a");
+        assert_eq!(chars("a-"), "-a");
+        assert_eq!(chars("a-c-e"), "-abce");
+        assert_eq!(chars(r"\--/"), "-./");
+        assert_eq!(chars(r"a\-c"), "-ac");
+        assert_eq!(chars(""), "");
+
+        assert_eq!(
+            "z-a".parse::<CharClass>(),
+            Err(CharClassError::ReversedRange {
+                start: 'z',
+                end: 'a'
+            })
+        );
+        assert_eq!(
+            "a\\".parse::<CharClass>(),
+            Err(CharClassError::TrailingEscape)
+        );
+    }
+
+    #[test]
+    fn rank() {
+        let class: CharClass = "0-9a-fxz".parse().unwrap();
+        assert_eq!(class.len(), 18);
+        for i in 0..class.len() {
+            let c = class.get(i).unwrap();
+            assert_eq!(class.rank(c), Some(i));
+        }
+        assert_eq!(class.get(18), None);
+        assert_eq!(class.get(usize::MAX), None);
+        assert_eq!(class.rank('g'), None);
+        assert_eq!(class.rank('y'), None);
+        assert_eq!(class.rank('/'), None);
+        assert_eq!(CharClass::new().get(0), None);
+    }
+
+    #[test]
+    fn surrogates() {
+        let class = CharClass::from('\u{D7FF}'..='\u{E000}');
+        assert_eq!(class.len(), 2);
+        assert_eq!(class.get(1), Some('\u{E000}'));
+        assert_eq!(class.rank('\u{E000}'), Some(1));
+
+        let all = CharClass::from('\0'..=char::MAX);
+        assert_eq!(all.len(), 0x110000 - 0x800);
+        assert_eq!(all.get(all.len() - 1), Some(char::MAX));
+    }
+
+    #[test]
+    fn set_operations() {
+        let hex: CharClass = "0-9a-f".parse().unwrap();
+        let letters = CharClass::from('a'..='z');
+        assert_eq!(hex.union(&letters), "0-9a-z".parse().unwrap());
+        assert_eq!(letters.difference(&hex), CharClass::from('g'..='z'));
+        assert_eq!(hex.difference(&letters), CharClass::from('0'..='9'));
+
+        let vowels: CharClass = "aeiou".parse().unwrap();
+        let consonants = vowels.negate(&letters);
+        assert_eq!(consonants, "b-df-hj-np-tv-z".parse().unwrap());
+        assert_eq!(consonants.negate(&letters), vowels);
+        assert_eq!(letters.negate(&letters), CharClass::new());
+        assert_eq!(CharClass::new().negate(&letters), letters);
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn unicode() {
+        let cyrillic = CharClass::script("Cyrillic").unwrap();
+        assert!(cyrillic.contains('ж') && cyrillic.contains('Ё'));
+        assert!(!cyrillic.contains('a'));
+        assert_eq!(cyrillic, CharClass::script("Cyrl").unwrap());
+
+        let han = CharClass::script("Han").unwrap();
+        assert!(han.len() > 90_000);
+        assert!(han.contains('字'));
+
+        let marks = CharClass::general_category("M").unwrap();
+        assert!(marks.contains('\u{301}'));
+        let upper = CharClass::general_category("Lu").unwrap();
+        assert!(upper.contains('É') && !upper.contains('é'));
+        assert!(CharClass::binary_property("Emoji").unwrap().contains('🦀'));
+
+        // Unassigned code points are only in Cn
+        let unassigned = CharClass::general_category("Cn").unwrap();
+        assert!(unassigned.contains('\u{378}'));
+        assert!(!cyrillic.contains('\u{378}'));
+        let all = CharClass::from('\0'..=char::MAX);
+        assert_eq!(all.assigned().len(), all.len() - unassigned.len());
+        assert_eq!(all.assigned().union(&unassigned), all);
+
+        for name in ["Klingon", "", "Greek}|\\d", "gc=Lu"] {
+            assert_eq!(
+                CharClass::script(name),
+                Err(CharClassError::UnknownProperty(name.into()))
+            );
+        }
+        assert!(CharClass::general_category("Cyrillic").is_err());
+    }
+
+    #[test]
+    fn generator() {
+        let g = Generator::class("a-cxz");
+        assert_eq!(g.len(), 5);
+        let values: Vec<_> = g.generate_all().collect();
+        assert_eq!(values, ["a", "b", "c", "x", "z"]);
+        assert_eq!(Generator::from('a'..='c'), Generator::class("abc"));
+
+        let g = Generator::class("ab") * 2 + Generator::from('x'..='z');
+        for (i, s) in g.generate_all().enumerate() {
+            assert_eq!(g.index_of(&s), Some(i as u128));
+            let mut visited = String::new();
+            g.visit_one(i as u128, |part| visited.push_str(&part));
+            assert_eq!(visited, s);
+        }
+        assert!(!g.matches("abc"));
+        assert!(Generator::class("").is_empty());
+    }
+}
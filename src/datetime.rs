@@ -0,0 +1,791 @@
+//! Ranges of dates and times, for use as [`Generator::DateTime`].
+use crate::error::DateTimeError;
+use crate::{Generator, IntFormat};
+use std::ops::RangeInclusive;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// Whether a range counts days, seconds of a day, or seconds of many days.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Kind {
+    Date,
+    Time,
+    DateTime,
+}
+
+/// One part of a strftime-style format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Field {
+    Literal(String),
+    /// `%Y`, four digits
+    Year,
+    /// `%y`, the last two digits of the year
+    ShortYear,
+    /// `%m`, from `01`
+    Month,
+    /// `%B` or `%b`, such as `February` or `Feb`
+    MonthName {
+        full: bool,
+    },
+    /// `%d`, from `01`
+    Day,
+    /// `%j`, from `001`
+    DayOfYear,
+    /// `%A` or `%a`, such as `Thursday` or `Thu`
+    Weekday {
+        full: bool,
+    },
+    /// `%H`, from `00` to `23`
+    Hour,
+    /// `%I`, from `01` to `12`
+    Hour12,
+    /// `%p`, `AM` or `PM`
+    AmPm,
+    /// `%M`
+    Minute,
+    /// `%S`
+    Second,
+}
+
+impl Field {
+    fn is_date(&self) -> bool {
+        use Field::*;
+        matches!(
+            self,
+            Year | ShortYear | Month | MonthName { .. } | Day | DayOfYear | Weekday { .. }
+        )
+    }
+
+    fn is_time(&self) -> bool {
+        use Field::*;
+        matches!(self, Hour | Hour12 | AmPm | Minute | Second)
+    }
+}
+
+/// Parses a strftime-style `format` into its fields, which must apply to the `kind` of range.
+fn parse_format(format: &str, kind: Kind) -> Result<Vec<Field>, DateTimeError> {
+    use Field::*;
+    let mut fields = vec![];
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        let spec = chars.clone().next();
+        let field = match c {
+            '%' => match chars.next() {
+                Some('%') => Literal("%".into()),
+                Some('Y') => Year,
+                Some('y') => ShortYear,
+                Some('m') => Month,
+                Some('B') => MonthName { full: true },
+                Some('b') => MonthName { full: false },
+                Some('d') => Day,
+                Some('j') => DayOfYear,
+                Some('A') => Weekday { full: true },
+                Some('a') => Weekday { full: false },
+                Some('H') => Hour,
+                Some('I') => Hour12,
+                Some('p') => AmPm,
+                Some('M') => Minute,
+                Some('S') => Second,
+                Some('F') if kind != Kind::Time => {
+                    fields.extend(parse_format("%Y-%m-%d", kind)?);
+                    continue;
+                }
+                Some('T') if kind != Kind::Date => {
+                    fields.extend(parse_format("%H:%M:%S", kind)?);
+                    continue;
+                }
+                Some(c @ ('F' | 'T')) => {
+                    return Err(DateTimeError::MisplacedSpecifier(format!("%{c}")))
+                }
+                Some(c) => return Err(DateTimeError::UnknownSpecifier(format!("%{c}"))),
+                None => return Err(DateTimeError::UnknownSpecifier("%".into())),
+            },
+            c => Literal(c.into()),
+        };
+        if (field.is_date() && kind == Kind::Time) || (field.is_time() && kind == Kind::Date) {
+            let spec = spec.expect("only specifiers are dates or times");
+            return Err(DateTimeError::MisplacedSpecifier(format!("%{spec}")));
+        }
+        match (fields.last_mut(), field) {
+            (Some(Literal(s)), Literal(t)) => s.push_str(&t),
+            (_, field) => fields.push(field),
+        }
+    }
+    Ok(fields)
+}
+
+/// The days from 1970-01-01 to the given date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    // Count from March, so that a leap day is at the end of the year
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = i64::from((153 * ((month + 9) % 12) + 2) / 5 + day - 1);
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The year, month, and day that is `days` from 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    } as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// The fields read from a string, before they're checked against each other.
+#[derive(Default)]
+struct Parsed {
+    year: Option<i64>,
+    short_year: Option<i64>,
+    month: Option<u32>,
+    day: Option<u32>,
+    day_of_year: Option<u32>,
+    hour: Option<i64>,
+    hour12: Option<i64>,
+    pm: Option<bool>,
+    minute: Option<i64>,
+    second: Option<i64>,
+}
+
+/// Reads the `len` digits at the start of `s`.
+fn take_digits(s: &str, len: usize) -> Option<u32> {
+    let digits = s.get(..len)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Finds which of `names`, or their three-letter abbreviations, `s` starts with.
+fn take_name(s: &str, names: &[&str], full: bool) -> Option<(usize, usize)> {
+    names.iter().enumerate().find_map(|(i, name)| {
+        let name = if full { name } else { &name[..3] };
+        s.starts_with(name).then_some((i, name.len()))
+    })
+}
+
+/// A range of dates, times of day, or both, each written once in ascending order with a
+/// strftime-style format.
+///
+/// A range of dates counts days and the others count seconds, so every value is a real date and
+/// time: there's no February 30th, and February 29th is only in leap years. Years are from 0 to
+/// 9999 of the proleptic Gregorian calendar, without time zones or leap seconds.
+///
+/// The format supports these specifiers, and other characters are written as they are:
+///
+/// | Specifier | Meaning                                  | Example   |
+/// |-----------|------------------------------------------|-----------|
+/// | `%Y`      | Year                                     | `2024`    |
+/// | `%y`      | Year without the century                 | `24`      |
+/// | `%m`      | Month                                    | `02`      |
+/// | `%B`      | Month name                               | `February`|
+/// | `%b`      | Abbreviated month name                   | `Feb`     |
+/// | `%d`      | Day of the month                         | `29`      |
+/// | `%j`      | Day of the year                          | `060`     |
+/// | `%A`      | Weekday name                             | `Thursday`|
+/// | `%a`      | Abbreviated weekday name                 | `Thu`     |
+/// | `%H`      | Hour                                     | `13`      |
+/// | `%I`      | Hour of a 12-hour clock                  | `01`      |
+/// | `%p`      | `AM` or `PM`                             | `PM`      |
+/// | `%M`      | Minute                                   | `05`      |
+/// | `%S`      | Second                                   | `59`      |
+/// | `%F`      | `%Y-%m-%d`                               |           |
+/// | `%T`      | `%H:%M:%S`                               |           |
+/// | `%%`      | `%`                                      |           |
+///
+/// The format must identify each value, so that none is written twice: a date needs its year and
+/// either its month and day or its day of the year, and a time needs its hour (with `%p` for
+/// `%I`), minute, and second. `%y` can only stand in for the year if every value is in the same
+/// century.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "DateTimeParts", try_from = "DateTimeParts")
+)]
+pub struct DateTimeRange {
+    /// Seconds from 1970-01-01 or, for times of day, from midnight
+    start: i64,
+    end: i64,
+
+    /// The seconds between consecutive values
+    step: i64,
+
+    kind: Kind,
+    fields: Vec<Field>,
+    format: String,
+}
+
+/// A [`DateTimeRange`] as it's serialized, with its bounds written as they are given to
+/// [`DateTimeRange::dates`], [`times`](DateTimeRange::times), or
+/// [`datetimes`](DateTimeRange::datetimes)
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DateTimeParts {
+    kind: Kind,
+    start: String,
+    end: String,
+    format: String,
+}
+
+#[cfg(feature = "serde")]
+impl From<DateTimeRange> for DateTimeParts {
+    fn from(range: DateTimeRange) -> Self {
+        let bounds = DateTimeRange::bounds(range.kind);
+        let (mut start, mut end) = (String::new(), String::new());
+        bounds.write(range.start, &mut start);
+        bounds.write(range.end, &mut end);
+        Self {
+            kind: range.kind,
+            start,
+            end,
+            format: range.format,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<DateTimeParts> for DateTimeRange {
+    type Error = DateTimeError;
+
+    fn try_from(parts: DateTimeParts) -> Result<Self, Self::Error> {
+        Self::new(parts.kind, &parts.start, &parts.end, &parts.format)
+    }
+}
+
+impl DateTimeRange {
+    /// Each date from `start` to `end`, which are written as `2024-02-29`.
+    ///
+    /// ```
+    /// use generator_combinator::DateTimeRange;
+    /// let range = DateTimeRange::dates("2024-02-28", "2024-03-01", "%d %b %Y").unwrap();
+    /// assert_eq!(range.format(), "%d %b %Y");
+    /// assert!(DateTimeRange::dates("2023-02-29", "2023-03-01", "%F").is_err());
+    /// assert!(DateTimeRange::dates("2024-01-01", "2024-12-31", "%Y-%m").is_err());
+    /// ```
+    pub fn dates(start: &str, end: &str, format: &str) -> Result<Self, DateTimeError> {
+        Self::new(Kind::Date, start, end, format)
+    }
+
+    /// Each second of the day from `start` to `end`, which are written as `13:05:59`.
+    pub fn times(start: &str, end: &str, format: &str) -> Result<Self, DateTimeError> {
+        Self::new(Kind::Time, start, end, format)
+    }
+
+    /// Each second from `start` to `end`, which are written as `2024-02-29T13:05:59`.
+    pub fn datetimes(start: &str, end: &str, format: &str) -> Result<Self, DateTimeError> {
+        Self::new(Kind::DateTime, start, end, format)
+    }
+
+    /// Any date or time that can be written with four-digit years, in the format of the bounds
+    /// of a range of `kind`.
+    fn bounds(kind: Kind) -> Self {
+        let format = match kind {
+            Kind::Date => "%Y-%m-%d",
+            Kind::Time => "%H:%M:%S",
+            Kind::DateTime => "%Y-%m-%dT%H:%M:%S",
+        };
+        Self {
+            start: days_from_civil(0, 1, 1) * SECONDS_PER_DAY,
+            end: days_from_civil(10_000, 1, 1) * SECONDS_PER_DAY - 1,
+            step: 1,
+            kind,
+            fields: parse_format(format, kind).expect("the format of bounds is valid"),
+            format: format.to_string(),
+        }
+    }
+
+    fn new(kind: Kind, start: &str, end: &str, format: &str) -> Result<Self, DateTimeError> {
+        let bounds = Self::bounds(kind);
+        let parse_bound = |s: &str| match bounds.parse_prefixes(s).as_slice() {
+            [(len, offset)] if *len == s.len() => Ok(bounds.get(*offset)),
+            _ => Err(DateTimeError::InvalidBound(s.to_string())),
+        };
+        let (start, end) = (parse_bound(start)?, parse_bound(end)?);
+        if start > end {
+            return Err(DateTimeError::ReversedRange);
+        }
+
+        use Field::*;
+        let fields = parse_format(format, kind)?;
+        let has = |field: Field| fields.contains(&field);
+        let same_century = bounds.year(start) / 100 == bounds.year(end) / 100;
+        let dated = (has(Year) || (same_century && has(ShortYear)))
+            && (has(DayOfYear)
+                || ((has(Month)
+                    || has(MonthName { full: true })
+                    || has(MonthName { full: false }))
+                    && has(Day)));
+        let timed = (has(Hour) || (has(Hour12) && has(AmPm))) && has(Minute) && has(Second);
+        let complete = match kind {
+            Kind::Date => dated,
+            Kind::Time => timed,
+            Kind::DateTime => dated && timed,
+        };
+        if !complete {
+            return Err(DateTimeError::AmbiguousFormat(format.to_string()));
+        }
+
+        let step = match kind {
+            Kind::Date => SECONDS_PER_DAY,
+            Kind::Time | Kind::DateTime => 1,
+        };
+        Ok(Self {
+            start,
+            end,
+            step,
+            kind,
+            fields,
+            format: format.to_string(),
+        })
+    }
+
+    /// The strftime-style format values are written with.
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    /// The number of values less one.
+    pub(crate) fn span(&self) -> u128 {
+        ((self.end - self.start) / self.step) as u128
+    }
+
+    /// The value at `offset` from the start, which must not exceed [`span`](Self::span).
+    pub(crate) fn get(&self, offset: u128) -> i64 {
+        self.start + offset as i64 * self.step
+    }
+
+    /// The year of the value `t`.
+    fn year(&self, t: i64) -> i64 {
+        civil_from_days(t.div_euclid(SECONDS_PER_DAY)).0
+    }
+
+    /// Appends the value `t` to `out`, as the format describes.
+    pub(crate) fn write(&self, t: i64, out: &mut String) {
+        let days = t.div_euclid(SECONDS_PER_DAY);
+        let seconds = t.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        let (hour, minute, second) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        for field in &self.fields {
+            match field {
+                Field::Literal(s) => out.push_str(s),
+                Field::Year => out.push_str(&format!("{year:04}")),
+                Field::ShortYear => out.push_str(&format!("{:02}", year % 100)),
+                Field::Month => out.push_str(&format!("{month:02}")),
+                Field::MonthName { full } => {
+                    let name = MONTHS[month as usize - 1];
+                    out.push_str(if *full { name } else { &name[..3] });
+                }
+                Field::Day => out.push_str(&format!("{day:02}")),
+                Field::DayOfYear => {
+                    let day_of_year = days - days_from_civil(year, 1, 1) + 1;
+                    out.push_str(&format!("{day_of_year:03}"));
+                }
+                Field::Weekday { full } => {
+                    // 1970-01-01 was a Thursday
+                    let name = WEEKDAYS[(days + 4).rem_euclid(7) as usize];
+                    out.push_str(if *full { name } else { &name[..3] });
+                }
+                Field::Hour => out.push_str(&format!("{hour:02}")),
+                Field::Hour12 => {
+                    let hour = if hour % 12 == 0 { 12 } else { hour % 12 };
+                    out.push_str(&format!("{hour:02}"));
+                }
+                Field::AmPm => out.push_str(if hour < 12 { "AM" } else { "PM" }),
+                Field::Minute => out.push_str(&format!("{minute:02}")),
+                Field::Second => out.push_str(&format!("{second:02}")),
+            }
+        }
+    }
+
+    /// Finds the prefix of `s` that is a value of this range, if there is one, returning its
+    /// length and its offset from the start.
+    pub(crate) fn parse_prefixes(&self, s: &str) -> Vec<(usize, u128)> {
+        let Some((len, t)) = self.parse_value(s) else {
+            return vec![];
+        };
+        if t < self.start || t > self.end || (t - self.start) % self.step != 0 {
+            return vec![];
+        }
+
+        // Only values written just as they would be are produced, which also rules out
+        // impossible dates and weekdays that don't match their dates
+        let mut written = String::new();
+        self.write(t, &mut written);
+        if written != s[..len] {
+            return vec![];
+        }
+        vec![(len, ((t - self.start) / self.step) as u128)]
+    }
+
+    /// Reads the value at the start of `s`, returning its length and the value, without checking
+    /// that it's written as it would be.
+    fn parse_value(&self, s: &str) -> Option<(usize, i64)> {
+        let mut parsed = Parsed::default();
+        let mut pos = 0;
+        for field in &self.fields {
+            let rest = &s[pos..];
+            let number = |len: usize| take_digits(rest, len).map(|n| (n, len));
+            pos += match field {
+                Field::Literal(literal) => rest
+                    .starts_with(literal.as_str())
+                    .then_some(literal.len())?,
+                Field::Year => {
+                    let (n, len) = number(4)?;
+                    parsed.year = Some(n.into());
+                    len
+                }
+                Field::ShortYear => {
+                    let (n, len) = number(2)?;
+                    parsed.short_year = Some(n.into());
+                    len
+                }
+                Field::Month => {
+                    let (n, len) = number(2)?;
+                    parsed.month = Some(n);
+                    len
+                }
+                Field::MonthName { full } => {
+                    let (i, len) = take_name(rest, &MONTHS, *full)?;
+                    parsed.month = Some(i as u32 + 1);
+                    len
+                }
+                Field::Day => {
+                    let (n, len) = number(2)?;
+                    parsed.day = Some(n);
+                    len
+                }
+                Field::DayOfYear => {
+                    let (n, len) = number(3)?;
+                    parsed.day_of_year = Some(n);
+                    len
+                }
+                Field::Weekday { full } => take_name(rest, &WEEKDAYS, *full)?.1,
+                Field::Hour => {
+                    let (n, len) = number(2)?;
+                    parsed.hour = Some(n.into());
+                    len
+                }
+                Field::Hour12 => {
+                    let (n, len) = number(2)?;
+                    parsed.hour12 = Some(n.into());
+                    len
+                }
+                Field::AmPm => {
+                    let (i, len) = take_name(rest, &["AM", "PM"], true)?;
+                    parsed.pm = Some(i == 1);
+                    len
+                }
+                Field::Minute => {
+                    let (n, len) = number(2)?;
+                    parsed.minute = Some(n.into());
+                    len
+                }
+                Field::Second => {
+                    let (n, len) = number(2)?;
+                    parsed.second = Some(n.into());
+                    len
+                }
+            };
+        }
+
+        let mut t = 0;
+        if self.kind != Kind::Time {
+            let year = match (parsed.year, parsed.short_year) {
+                (Some(year), _) => year,
+                (None, short_year) => self.year(self.start) / 100 * 100 + short_year?,
+            };
+            let days = match (parsed.month, parsed.day, parsed.day_of_year) {
+                (Some(month), Some(day), _)
+                    if (1..=12).contains(&month) && (1..=31).contains(&day) =>
+                {
+                    days_from_civil(year, month, day)
+                }
+                (_, _, Some(day_of_year)) if (1..=366).contains(&day_of_year) => {
+                    days_from_civil(year, 1, 1) + i64::from(day_of_year) - 1
+                }
+                _ => return None,
+            };
+            t += days * SECONDS_PER_DAY;
+        }
+        if self.kind != Kind::Date {
+            let hour = match (parsed.hour, parsed.hour12, parsed.pm) {
+                (Some(hour), _, _) => hour,
+                (None, Some(hour), Some(pm)) => hour % 12 + if pm { 12 } else { 0 },
+                _ => return None,
+            };
+            t += hour * 3600 + parsed.minute? * 60 + parsed.second?;
+        }
+        Some((pos, t))
+    }
+
+    /// A generator of every combination of each field's values, which includes all of this
+    /// range's values but may include impossible dates too.
+    pub(crate) fn to_generator(&self) -> Generator {
+        let number = |range: RangeInclusive<i128>, width: usize| {
+            let format = IntFormat {
+                width,
+                ..Default::default()
+            };
+            Generator::int_range_with(range, format)
+        };
+        let names = |names: &[&str], full: bool| {
+            let names: Vec<_> = names
+                .iter()
+                .map(|name| if full { *name } else { &name[..3] })
+                .collect();
+            Generator::from(&names[..])
+        };
+        let years: RangeInclusive<i128> = self.year(self.start).into()..=self.year(self.end).into();
+        let short_years = if years.start() / 100 == years.end() / 100 {
+            years.start() % 100..=years.end() % 100
+        } else {
+            0..=99
+        };
+
+        let parts = self
+            .fields
+            .iter()
+            .map(|field| match field {
+                Field::Literal(s) => Generator::from(s.as_str()),
+                Field::Year => number(years.clone(), 4),
+                Field::ShortYear => number(short_years.clone(), 2),
+                Field::Month => number(1..=12, 2),
+                Field::MonthName { full } => names(&MONTHS, *full),
+                Field::Day => number(1..=31, 2),
+                Field::DayOfYear => number(1..=366, 3),
+                Field::Weekday { full } => names(&WEEKDAYS, *full),
+                Field::Hour => number(0..=23, 2),
+                Field::Hour12 => number(1..=12, 2),
+                Field::AmPm => names(&["AM", "PM"], true),
+                Field::Minute | Field::Second => number(0..=59, 2),
+            })
+            .collect();
+        Generator::Sequence(parts)
+    }
+}
+
+impl From<DateTimeRange> for Generator {
+    fn from(range: DateTimeRange) -> Self {
+        Generator::DateTime(range)
+    }
+}
+
+impl Generator {
+    /// Generates each date from `start` to `end`, both written as `2024-02-29`, in the given
+    /// strftime-style format. See [`DateTimeRange`] for the supported format.
+    ///
+    /// Panics if a bound or the format is invalid; [`DateTimeRange::dates`] returns an error
+    /// instead.
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// let g = Generator::dates("2024-02-27", "2024-03-01", "%Y-%m-%d");
+    /// assert_eq!(g.len(), 4);
+    /// assert_eq!(g.generate_one(2), "2024-02-29");
+    /// assert_eq!(g.generate_one(3), "2024-03-01");
+    ///
+    /// let g = Generator::dates("1900-01-01", "2099-12-31", "%a, %d %B %Y");
+    /// assert_eq!(g.len(), 73_049);
+    /// assert_eq!(g.index_of("Sat, 01 January 2000"), Some(36_524));
+    /// assert_eq!(g.index_of("Fri, 01 January 2000"), None);
+    /// assert_eq!(g.index_of("Thu, 29 February 1900"), None);
+    /// ```
+    pub fn dates(start: &str, end: &str, format: &str) -> Self {
+        DateTimeRange::dates(start, end, format)
+            .unwrap_or_else(|e| panic!("{e}"))
+            .into()
+    }
+
+    /// Generates each second of the day from `start` to `end`, both written as `13:05:59`, in
+    /// the given strftime-style format. See [`DateTimeRange`] for the supported format.
+    ///
+    /// Panics if a bound or the format is invalid; [`DateTimeRange::times`] returns an error
+    /// instead.
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// let g = Generator::times("00:00:00", "23:59:59", "%I:%M:%S %p");
+    /// assert_eq!(g.len(), 86_400);
+    /// assert_eq!(g.generate_one(0), "12:00:00 AM");
+    /// assert_eq!(g.generate_one(47_159), "01:05:59 PM");
+    /// assert_eq!(g.regex(), r"(0[1-9]|1[0-2]):[0-5]\d:[0-5]\d (AM|PM)");
+    /// ```
+    pub fn times(start: &str, end: &str, format: &str) -> Self {
+        DateTimeRange::times(start, end, format)
+            .unwrap_or_else(|e| panic!("{e}"))
+            .into()
+    }
+
+    /// Generates each second from `start` to `end`, both written as `2024-02-29T13:05:59`, in
+    /// the given strftime-style format, such as `%Y-%m-%dT%H:%M:%SZ` for RFC 3339 timestamps in
+    /// UTC. See [`DateTimeRange`] for the supported format.
+    ///
+    /// Panics if a bound or the format is invalid; [`DateTimeRange::datetimes`] returns an error
+    /// instead.
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// let g = Generator::datetimes("2024-01-01T00:00:00", "2024-12-31T23:59:59", "%FT%TZ");
+    /// assert_eq!(g.len(), 366 * 86_400);
+    /// assert_eq!(g.generate_one(59 * 86_400 + 47_159), "2024-02-29T13:05:59Z");
+    /// assert_eq!(g.index_of("2024-12-31T23:59:59Z"), Some(366 * 86_400 - 1));
+    /// ```
+    pub fn datetimes(start: &str, end: &str, format: &str) -> Self {
+        DateTimeRange::datetimes(start, end, format)
+            .unwrap_or_else(|e| panic!("{e}"))
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RegexError;
+
+    #[test]
+    fn calendar() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(days_from_civil(0, 1, 1)), (0, 1, 1));
+        for days in days_from_civil(1599, 1, 1)..days_from_civil(2401, 1, 1) {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+
+        let leap_days =
+            |year| Generator::dates(&format!("{year}-01-01"), &format!("{year}-12-31"), "%F").len();
+        assert_eq!(leap_days(1900), 365);
+        assert_eq!(leap_days(2000), 366);
+        assert_eq!(leap_days(2023), 365);
+        assert_eq!(leap_days(2024), 366);
+    }
+
+    #[test]
+    fn round_trip() {
+        let g = Generator::dates("2009-12-25", "2011-01-05", "%A %j %y %b %d");
+        let values: Vec<_> = g.generate_all().collect();
+        assert_eq!(values[0], "Friday 359 09 Dec 25");
+        assert_eq!(values[values.len() - 1], "Wednesday 005 11 Jan 05");
+        for (i, s) in values.iter().enumerate() {
+            assert_eq!(g.indices_of(s), [i as u128]);
+        }
+
+        let g = Generator::datetimes("2023-12-31T23:59:58", "2024-01-01T00:00:01", "%F %T");
+        let values: Vec<_> = g.generate_all().collect();
+        assert_eq!(
+            values,
+            [
+                "2023-12-31 23:59:58",
+                "2023-12-31 23:59:59",
+                "2024-01-01 00:00:00",
+                "2024-01-01 00:00:01"
+            ]
+        );
+
+        // A value can be followed by more of the same
+        let g = Generator::times("09:00:00", "09:00:10", "%H%M%S") * 2;
+        assert_eq!(g.index_of("090003090010"), Some(3 * 11 + 10));
+        assert!(!g.matches("090011090010"));
+
+        // Day zero isn't a date
+        let g = Generator::dates("2024-01-01", "2024-12-31", "%F");
+        assert!(!g.matches("2024-03-00"));
+        assert!(!g.matches("2024-00-01"));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            DateTimeRange::dates("2024-02-30", "2024-03-01", "%F"),
+            Err(DateTimeError::InvalidBound("2024-02-30".into()))
+        );
+        assert_eq!(
+            DateTimeRange::dates("2024-03-00", "2024-03-01", "%F"),
+            Err(DateTimeError::InvalidBound("2024-03-00".into()))
+        );
+        assert_eq!(
+            DateTimeRange::dates("2024-000", "2024-001", "%Y-%j"),
+            Err(DateTimeError::InvalidBound("2024-000".into()))
+        );
+        assert_eq!(
+            DateTimeRange::times("12:00", "13:00:00", "%T"),
+            Err(DateTimeError::InvalidBound("12:00".into()))
+        );
+        assert_eq!(
+            DateTimeRange::dates("2024-03-01", "2024-02-01", "%F"),
+            Err(DateTimeError::ReversedRange)
+        );
+        assert_eq!(
+            DateTimeRange::dates("2024-01-01", "2024-02-01", "%F %Q"),
+            Err(DateTimeError::UnknownSpecifier("%Q".into()))
+        );
+        assert!(matches!(
+            DateTimeRange::dates("2024-01-01", "2024-02-01", "%F %H"),
+            Err(DateTimeError::MisplacedSpecifier(_))
+        ));
+        assert!(matches!(
+            DateTimeRange::times("00:00:00", "01:00:00", "%I:%M:%S"),
+            Err(DateTimeError::AmbiguousFormat(_))
+        ));
+        assert!(matches!(
+            DateTimeRange::dates("1999-01-01", "2000-01-01", "%y-%m-%d"),
+            Err(DateTimeError::AmbiguousFormat(_))
+        ));
+    }
+
+    #[test]
+    fn regex() {
+        let g = Generator::dates("2019-06-01", "2024-02-29", "%F");
+        assert_eq!(
+            g.regex(),
+            r"20(19|2[0-4])-(0[1-9]|1[0-2])-(0[1-9]|[12]\d|3[01])"
+        );
+        assert_eq!(g.try_regex(), Err(RegexError::DateTimeRange));
+
+        let g = Generator::times("00:00:00", "23:59:59", "%T");
+        assert_eq!(
+            g.try_regex(),
+            Ok(r"([01]\d|2[0-3]):[0-5]\d:[0-5]\d".to_string())
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt::Display;
+
+/// Errors from functions on [`Generator`](crate::Generator) that depend on how many values it
+/// represents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CardinalityError {
+    /// The number of values represented by the generator doesn't fit in a `u128`.
+    Overflow,
+
+    /// The requested index is not less than the number of values represented by the generator.
+    IndexOutOfRange { index: u128, len: u128 },
+
+    /// More distinct values were requested than the generator represents.
+    NotEnoughValues { requested: usize, len: u128 },
+}
+
+impl Display for CardinalityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardinalityError::Overflow => {
+                write!(f, "the number of generated values exceeds u128::MAX")
+            }
+            CardinalityError::IndexOutOfRange { index, len } => {
+                write!(
+                    f,
+                    "index {index} is out of range for a generator of {len} values"
+                )
+            }
+            CardinalityError::NotEnoughValues { requested, len } => {
+                write!(
+                    f,
+                    "{requested} distinct values were requested from a generator of {len} values"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CardinalityError {}
+
+/// Errors from [`Generator::try_regex`](crate::Generator::try_regex) and
+/// [`Generator::regex_with`](crate::Generator::regex_with).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegexError {
+    /// The generator contains a [`Transform`](crate::Generator::Transform), whose output can't be
+    /// described without knowing what the transformation does. Of the
+    /// [`BuiltinTransform`](crate::BuiltinTransform)s, only prefixes, suffixes, reversals, and
+    /// case changes can be rendered.
+    OpaqueTransform,
+
+    /// The generator contains a [`DateTime`](crate::Generator::DateTime) range, whose values
+    /// depend on the lengths of months and on leap years in ways that the regex doesn't describe.
+    DateTimeRange,
+
+    /// The generator contains a [`Filter`](crate::Generator::Filter), whose kept values can't be
+    /// described without knowing what its predicate does.
+    Filter,
+}
+
+impl Display for RegexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegexError::OpaqueTransform => {
+                write!(
+                    f,
+                    "a transformed generator can't be rendered as an exact regex"
+                )
+            }
+            RegexError::DateTimeRange => {
+                write!(f, "a range of dates can't be rendered as an exact regex")
+            }
+            RegexError::Filter => {
+                write!(
+                    f,
+                    "a filtered generator can't be rendered as an exact regex"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegexError {}
+
+/// Errors from parsing a [`CharClass`](crate::CharClass).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CharClassError {
+    /// A range whose start comes after its end, such as `z-a`.
+    ReversedRange { start: char, end: char },
+
+    /// A `\` at the end of the class, with no character to escape.
+    TrailingEscape,
+
+    /// A Unicode general category, script, or property that doesn't exist.
+    UnknownProperty(String),
+}
+
+impl Display for CharClassError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CharClassError::ReversedRange { start, end } => {
+                write!(f, "the range {start}-{end} is reversed")
+            }
+            CharClassError::TrailingEscape => {
+                write!(f, "the character class ends with an incomplete escape")
+            }
+            CharClassError::UnknownProperty(name) => {
+                write!(f, "{name:?} isn't a known Unicode property")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CharClassError {}
+
+/// Errors from creating a [`DateTimeRange`](crate::DateTimeRange).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DateTimeError {
+    /// A bound that isn't a real date or time written as `2024-02-29`, `13:05:59`, or
+    /// `2024-02-29T13:05:59`.
+    InvalidBound(String),
+
+    /// A range whose start comes after its end.
+    ReversedRange,
+
+    /// A `%` specifier that isn't supported, such as `%Z`, or a `%` at the end of the format.
+    UnknownSpecifier(String),
+
+    /// A specifier for a date in a range of times, or for a time in a range of dates.
+    MisplacedSpecifier(String),
+
+    /// A format that leaves out part of a value, so that values would be written more than once.
+    AmbiguousFormat(String),
+}
+
+impl Display for DateTimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateTimeError::InvalidBound(bound) => {
+                write!(f, "{bound:?} isn't a valid date or time")
+            }
+            DateTimeError::ReversedRange => write!(f, "the range's start comes after its end"),
+            DateTimeError::UnknownSpecifier(spec) => {
+                write!(f, "{spec:?} isn't a supported format specifier")
+            }
+            DateTimeError::MisplacedSpecifier(spec) => {
+                write!(f, "{spec:?} doesn't apply to this kind of range")
+            }
+            DateTimeError::AmbiguousFormat(format) => {
+                write!(
+                    f,
+                    "the format {format:?} would write some values more than once"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DateTimeError {}
+
+/// Errors from [`Generator::try_difference`](crate::Generator::try_difference) and
+/// [`Generator::try_intersection`](crate::Generator::try_intersection), for generators whose
+/// values can't be compared structurally.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SetOperationError {
+    /// A generator contains a [`Transform`](crate::Generator::Transform) whose values can't be
+    /// described without knowing what the transformation does.
+    OpaqueTransform,
+
+    /// A generator contains a [`DateTime`](crate::Generator::DateTime) range, whose values depend
+    /// on the lengths of months and on leap years.
+    DateTimeRange,
+
+    /// A generator contains a [`Filter`](crate::Generator::Filter), whose kept values can't be
+    /// described without knowing what its predicate does.
+    Filter,
+}
+
+impl Display for SetOperationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetOperationError::OpaqueTransform => {
+                write!(f, "a transformed generator can't be compared structurally")
+            }
+            SetOperationError::DateTimeRange => {
+                write!(f, "a range of dates can't be compared structurally")
+            }
+            SetOperationError::Filter => {
+                write!(f, "a filtered generator can't be compared structurally")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SetOperationError {}
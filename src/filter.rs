@@ -0,0 +1,274 @@
+//! Generators that keep only the values of another generator matching a predicate.
+use crate::shuffle::Permutation;
+use crate::Generator;
+use std::sync::Arc;
+
+/// The values of a [`Generator`] that match a predicate, created by [`Generator::filter`].
+///
+/// If the inner generator has at most [`EXACT_LIMIT`](Self::EXACT_LIMIT) values, they're all
+/// checked once when the filter is created, and the kept values are indexed compactly in their
+/// original order: the count is exact and each index maps to a different kept value.
+///
+/// Larger generators are sampled to estimate how many values are kept. Each index then selects a
+/// kept value by rejection sampling: it determines a pseudo-random order in which to try the
+/// inner generator's values until one matches. An index chosen at random gives a kept value
+/// chosen uniformly at random, but different indices may give the same value, and not every
+/// kept value necessarily has an index. At most [`MAX_ATTEMPTS`](Self::MAX_ATTEMPTS) values are
+/// tried for each index; if none of them is kept, the index gives one of the kept values found
+/// by the sample instead, so generating a value never checks more than that many. [`Generator::matches`] still recognizes every kept value,
+/// but [`Generator::index_of`] doesn't map any of them back to an index.
+#[derive(Clone)]
+pub struct Filter {
+    inner: Box<Generator>,
+    predicate: Arc<dyn Fn(&str) -> bool + Send + Sync>,
+
+    /// The number of values of `inner`
+    inner_len: u128,
+
+    /// The indices of the kept values of `inner`, if they were all checked
+    kept: Option<Arc<[u32]>>,
+
+    /// The indices of the kept values of `inner` found by the sample, if it was estimated
+    sampled: Arc<[u128]>,
+
+    /// The number of kept values, which is an estimate unless `kept` is known
+    len: u128,
+}
+
+impl Filter {
+    /// The most values a generator can have for its filter to be exact.
+    pub const EXACT_LIMIT: u128 = 1 << 20;
+
+    /// The number of values checked to estimate how many values of a larger generator are kept.
+    pub const SAMPLES: u128 = 1 << 14;
+
+    /// The most values tried by rejection sampling for each index of an estimated filter.
+    pub const MAX_ATTEMPTS: u128 = 1 << 18;
+
+    fn new(inner: Generator, predicate: Arc<dyn Fn(&str) -> bool + Send + Sync>) -> Self {
+        let inner_len = match inner.try_len() {
+            Ok(len) => len,
+            Err(e) => panic!("{e}"),
+        };
+
+        if inner_len <= Self::EXACT_LIMIT {
+            let kept: Arc<[u32]> = inner
+                .generate_all()
+                .enumerate()
+                .filter(|(_, s)| predicate(s))
+                .map(|(i, _)| i as u32)
+                .collect();
+            return Self {
+                len: kept.len() as u128,
+                inner: Box::new(inner),
+                predicate,
+                inner_len,
+                kept: Some(kept),
+                sampled: Arc::new([]),
+            };
+        }
+
+        // Sample distinct values in a fixed pseudo-random order, so the estimate is reproducible
+        let order = Permutation::new(inner_len, 0);
+        let sampled: Arc<[u128]> = (0..Self::SAMPLES)
+            .map(|i| order.apply(i))
+            .filter(|i| predicate(&inner.generate_one(*i)))
+            .collect();
+        let hits = sampled.len() as u128;
+        let len =
+            inner_len / Self::SAMPLES * hits + inner_len % Self::SAMPLES * hits / Self::SAMPLES;
+
+        Self {
+            inner: Box::new(inner),
+            predicate,
+            inner_len,
+            kept: None,
+            sampled,
+            // Rejection sampling needs at least one value to find
+            len: if hits > 0 { len.max(1) } else { 0 },
+        }
+    }
+
+    /// The generator whose values are filtered.
+    pub fn inner(&self) -> &Generator {
+        &self.inner
+    }
+
+    /// Whether every value of the inner generator was checked, so that the number of kept values
+    /// is exact and each index gives a different value.
+    pub fn is_exact(&self) -> bool {
+        self.kept.is_some()
+    }
+
+    pub(crate) fn len(&self) -> u128 {
+        self.len
+    }
+
+    /// Whether `s` is kept by the predicate.
+    pub(crate) fn keeps(&self, s: &str) -> bool {
+        (self.predicate)(s)
+    }
+
+    /// The index within the inner generator of the kept value at `offset`.
+    pub(crate) fn inner_index(&self, offset: u128) -> u128 {
+        if let Some(kept) = &self.kept {
+            return kept[offset as usize].into();
+        }
+
+        let seed = offset as u64 ^ (offset >> 64) as u64;
+        let order = Permutation::new(self.inner_len, seed);
+        (0..self.inner_len.min(Self::MAX_ATTEMPTS))
+            .map(|i| order.apply(i))
+            .find(|i| self.keeps(&self.inner.generate_one(*i)))
+            .unwrap_or_else(|| self.sampled[(seed % self.sampled.len() as u64) as usize])
+    }
+
+    /// The offset of the kept value at `index` within the inner generator, or `None` if it isn't
+    /// kept or the filter isn't exact.
+    pub(crate) fn offset_of(&self, index: u128) -> Option<u128> {
+        let index = u32::try_from(index).ok()?;
+        let offset = self.kept.as_ref()?.binary_search(&index).ok()?;
+        Some(offset as u128)
+    }
+}
+
+impl std::fmt::Debug for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Filter")
+            .field("inner", &self.inner)
+            .field("len", &self.len)
+            .field("exact", &self.is_exact())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Filters are equal if they filter equal generators with the same predicate (or its clones).
+impl PartialEq for Filter {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner && Arc::ptr_eq(&self.predicate, &other.predicate)
+    }
+}
+
+impl Generator {
+    /// Keeps only the values for which `predicate` returns `true`, in their original order.
+    ///
+    /// Generators with at most [`Filter::EXACT_LIMIT`] values are filtered exactly: `len` is the
+    /// number of values kept, and `generate_one` and `index_of` map between the kept values and
+    /// the indices `0..len`. For larger generators, `len` is an estimate from a sample of values,
+    /// and `generate_one` finds a kept value by rejection sampling, so a random index gives a
+    /// random kept value; see [`Filter`]. Creating a filter checks up to
+    /// [`Filter::EXACT_LIMIT`] values, and generating a value from an estimated filter checks up
+    /// to [`Filter::MAX_ATTEMPTS`].
+    ///
+    /// Panics if this generator has more than `u128::MAX` values.
+    ///
+    /// ```
+    /// use generator_combinator::{oneof, Generator};
+    /// let reserved = ["admin", "root"];
+    /// let usernames = (oneof!("admin", "root", "alice") + Generator::Digit.optional())
+    ///     .filter(move |s| !reserved.contains(&s));
+    /// assert_eq!(usernames.len(), 31);
+    /// assert_eq!(usernames.generate_one(0), "alice");
+    /// assert_eq!(usernames.generate_one(1), "admin0");
+    /// assert_eq!(usernames.index_of("admin0"), Some(1));
+    /// assert_eq!(usernames.index_of("admin"), None);
+    ///
+    /// // Numbers that aren't all zeros
+    /// let serials = (Generator::Digit * 6).filter(|s| s != "000000");
+    /// assert_eq!(serials.len(), 999_999);
+    /// assert_eq!(serials.generate_one(0), "000001");
+    /// ```
+    pub fn filter(self, predicate: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        Generator::Filter(Filter::new(self, Arc::new(predicate)))
+    }
+}
+
+impl From<Filter> for Generator {
+    fn from(filter: Filter) -> Self {
+        Generator::Filter(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shuffle::Permutation;
+    use crate::{oneof, Generator};
+
+    #[test]
+    fn exact() {
+        let g = (Generator::Digit * 3).filter(|s| s.chars().all(|c| c != '0'));
+        assert!(matches!(&g, Generator::Filter(f) if f.is_exact()));
+        assert_eq!(g.len(), 729);
+        let values: Vec<_> = g.generate_all().collect();
+        assert_eq!(values[0], "111");
+        assert_eq!(values[728], "999");
+        for (i, s) in values.iter().enumerate() {
+            assert_eq!(g.index_of(s), Some(i as u128));
+        }
+        assert_eq!(g.index_of("101"), None);
+
+        // Filters are components like any other
+        let g = oneof!("a", "b") + g + Generator::Digit.filter(|s| s != "5");
+        assert_eq!(g.len(), 2 * 729 * 9);
+        let s = g.generate_one(1000);
+        assert_eq!(g.index_of(&s), Some(1000));
+        let mut parts = vec![];
+        g.visit_one(1000, |part| parts.push(part));
+        assert_eq!(parts.concat(), s);
+
+        let none = Generator::AlphaLower.filter(|_| false);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn estimated() {
+        // About one value in ten ends with a 7
+        let g = (Generator::Digit * 8).filter(|s| s.ends_with('7'));
+        assert!(matches!(&g, Generator::Filter(f) if !f.is_exact()));
+        let len = g.len() as f64;
+        assert!((len - 1e7).abs() < 1e6, "{len}");
+
+        for i in (0..g.len()).step_by(999_983) {
+            assert!(g.generate_one(i).ends_with('7'));
+        }
+        assert_eq!(g.generate_one(12345), g.generate_one(12345));
+        assert!(g.try_regex().is_err());
+
+        // Kept values are recognized, but have no index
+        let s = g.generate_one(0);
+        assert!(g.matches(&s));
+        assert!(!g.matches("12345678"));
+        assert!(!g.matches("1234567"));
+        assert_eq!(g.index_of(&s), None);
+        assert_eq!(g.parse(&s), None);
+        let g = oneof!("x", "y") + g;
+        assert!(g.matches(&g.generate_one(1)));
+        assert!(g.matches(&format!("y{s}")));
+        assert!(!g.matches(&format!("z{s}")));
+
+        // A value too rare to turn up in the sample isn't counted
+        let g = (Generator::Digit * 8).filter(|s| s == "12345678");
+        assert!(g.is_empty());
+
+        // Values too rare for rejection sampling to find in time are taken from the sample
+        let first = Permutation::new(100_000_000, 0).apply(0);
+        let kept = format!("{first:08}");
+        let g = (Generator::Digit * 8).filter(move |s| *s == kept);
+        assert!(!g.is_empty());
+        for i in [0, 1, g.len() - 1] {
+            assert_eq!(g.generate_one(i), format!("{first:08}"));
+        }
+
+        // Rarer values take longer to find, but are still found
+        let g = (Generator::Digit * 8).filter(|s| s.starts_with('1') && s.ends_with("99"));
+        assert!(!g.is_empty());
+        assert!(g.generate_one(0).starts_with('1'));
+    }
+
+    #[test]
+    fn equality() {
+        let g = Generator::Digit.filter(|s| s != "0");
+        assert_eq!(g, g.clone());
+        assert_ne!(g, Generator::Digit.filter(|s| s != "0"));
+    }
+}
@@ -0,0 +1,658 @@
+//! Builds a [`Generator`] from a regular expression.
+use crate::{CharClass, Generator};
+use std::fmt::Display;
+
+/// Errors from [`Generator::from_regex`]. Each holds the byte offset in the pattern where the
+/// problem was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FromRegexError {
+    /// `*`, `+`, or `{m,}`, which match without limit and so can't be enumerated.
+    Unbounded { position: usize },
+
+    /// An anchor or word boundary such as `^`, `$`, or `\b`.
+    Anchor { position: usize },
+
+    /// A lookahead or lookbehind such as `(?=...)` or `(?<!...)`.
+    Lookaround { position: usize },
+
+    /// Valid regex syntax outside the supported subset, such as `.` or a backreference.
+    Unsupported { position: usize, what: String },
+
+    /// The pattern isn't a valid regex.
+    Syntax { position: usize, message: String },
+}
+
+impl Display for FromRegexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FromRegexError::Unbounded { position } => write!(
+                f,
+                "unbounded repetition at {position}; use a bounded repetition like {{m,n}} instead"
+            ),
+            FromRegexError::Anchor { position } => {
+                write!(
+                    f,
+                    "anchors and word boundaries are unsupported (at {position})"
+                )
+            }
+            FromRegexError::Lookaround { position } => {
+                write!(f, "lookarounds are unsupported (at {position})")
+            }
+            FromRegexError::Unsupported { position, what } => {
+                write!(f, "{what} is unsupported (at {position})")
+            }
+            FromRegexError::Syntax { position, message } => {
+                write!(f, "invalid regex at {position}: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FromRegexError {}
+
+impl Generator {
+    /// Builds a `Generator` producing exactly the strings matched (in full) by the regular
+    /// expression `pattern`.
+    ///
+    /// Only regexes matching finitely many strings are supported: literals, escapes like `\d`
+    /// and `\.`, character classes like `[a-fxz]`, alternation, groups, and the `?`, `{n}`, and
+    /// `{m,n}` quantifiers, which may be lazy (`??`, `{m,n}?`). Unbounded repetition (`*`, `+`,
+    /// `{m,}`), possessive quantifiers, anchors, lookarounds, and constructs such as `.` and
+    /// backreferences give a [`FromRegexError`].
+    ///
+    /// ```
+    /// use generator_combinator::{oneof, Generator};
+    /// let g = Generator::from_regex(r"(foo|bar)-\d{2,3}").unwrap();
+    /// assert_eq!(g, oneof!("foo", "bar") + '-' + Generator::Digit * (2, 3));
+    /// assert_eq!(g.len(), 2 * 1100);
+    ///
+    /// assert!(Generator::from_regex(r"\d+").is_err());
+    /// ```
+    pub fn from_regex(pattern: &str) -> Result<Self, FromRegexError> {
+        let mut parser = Parser { pattern, pos: 0 };
+        let g = parser.alternation()?;
+        match parser.peek() {
+            None => Ok(g),
+            Some(')') => Err(parser.syntax("unopened group")),
+            Some(_) => unreachable!("alternations end at ')' or the end of the pattern"),
+        }
+    }
+}
+
+/// A recursive-descent parser over `pattern`, currently at byte offset `pos`.
+struct Parser<'a> {
+    pattern: &'a str,
+    pos: usize,
+}
+
+/// One item in a sequence: either a literal character, which may be merged with its neighbors
+/// into a [`Generator::Str`], or any other generator.
+enum Item {
+    Literal(char),
+    Other(Generator),
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.pattern[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.pattern[self.pos..].starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn syntax(&self, message: &str) -> FromRegexError {
+        FromRegexError::Syntax {
+            position: self.pos,
+            message: message.into(),
+        }
+    }
+
+    fn unsupported(&self, position: usize, what: &str) -> FromRegexError {
+        FromRegexError::Unsupported {
+            position,
+            what: what.into(),
+        }
+    }
+
+    /// `concat ('|' concat)*`
+    fn alternation(&mut self) -> Result<Generator, FromRegexError> {
+        let mut alternatives = vec![self.concat()?];
+        while self.eat("|") {
+            alternatives.push(self.concat()?);
+        }
+
+        if alternatives.len() == 1 {
+            return Ok(alternatives.remove(0));
+        }
+
+        // An empty alternative, as in `(a|)`, makes the others optional, unless one of them can
+        // already be empty
+        let has_empty = alternatives.contains(&Generator::Empty);
+        alternatives.retain(|a| *a != Generator::Empty);
+        let is_optional = has_empty && !alternatives.iter().any(|a| a.matches(""));
+        let g = match alternatives.len() {
+            0 => return Ok(Generator::Empty),
+            1 => alternatives.remove(0),
+            _ => Generator::OneOf {
+                v: alternatives,
+                is_optional: false,
+            },
+        };
+        Ok(if is_optional { g.optional() } else { g })
+    }
+
+    /// A sequence of quantified atoms, up to the next `|` or `)`.
+    fn concat(&mut self) -> Result<Generator, FromRegexError> {
+        let mut items = vec![];
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let item = self.atom()?;
+            items.push(self.quantifiers(item)?);
+        }
+
+        // Merge runs of literals into strings
+        let mut parts = vec![];
+        let mut literal = String::new();
+        for item in items {
+            match item {
+                Item::Literal(c) => literal.push(c),
+                Item::Other(other) => {
+                    parts.extend(literal_generator(&mut literal));
+                    parts.push(other);
+                }
+            }
+        }
+        parts.extend(literal_generator(&mut literal));
+        Ok(parts.into_iter().reduce(|a, b| a + b).unwrap_or_default())
+    }
+
+    /// Applies any quantifiers following `item`.
+    fn quantifiers(&mut self, mut item: Item) -> Result<Item, FromRegexError> {
+        loop {
+            let start = self.pos;
+            let g = match self.peek() {
+                Some('*') | Some('+') => return Err(FromRegexError::Unbounded { position: start }),
+                Some('?') => {
+                    self.next();
+                    self.modifier()?;
+                    into_generator(item).optional()
+                }
+                Some('{') => {
+                    self.next();
+                    let (m, n) = self.repetition(start)?;
+                    self.modifier()?;
+                    into_generator(item) * (m, n)
+                }
+                _ => return Ok(item),
+            };
+            item = Item::Other(g);
+        }
+    }
+
+    /// Skips a lazy modifier `?` after a quantifier, which only changes which match is preferred,
+    /// or rejects a possessive modifier `+`, which can change the strings matched.
+    fn modifier(&mut self) -> Result<(), FromRegexError> {
+        let start = self.pos;
+        if self.eat("+") {
+            return Err(self.unsupported(start, "a possessive quantifier"));
+        }
+        self.eat("?");
+        Ok(())
+    }
+
+    /// The bounds of a `{n}` or `{m,n}` quantifier, after its opening brace.
+    fn repetition(&mut self, start: usize) -> Result<(usize, usize), FromRegexError> {
+        let m = self.number()?;
+        let n = if self.eat(",") {
+            if self.peek() == Some('}') {
+                return Err(FromRegexError::Unbounded { position: start });
+            }
+            self.number()?
+        } else {
+            m
+        };
+        if !self.eat("}") {
+            return Err(self.syntax("expected '}' to close the repetition"));
+        }
+        if m > n {
+            return Err(FromRegexError::Syntax {
+                position: start,
+                message: format!("the repetition {{{m},{n}}} has its bounds reversed"),
+            });
+        }
+        Ok((m, n))
+    }
+
+    fn number(&mut self) -> Result<usize, FromRegexError> {
+        let digits = self.pattern[self.pos..]
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .count();
+        let n = self.pattern[self.pos..self.pos + digits]
+            .parse()
+            .map_err(|_| self.syntax("expected a number in the repetition"))?;
+        self.pos += digits;
+        Ok(n)
+    }
+
+    fn atom(&mut self) -> Result<Item, FromRegexError> {
+        let start = self.pos;
+        let c = self
+            .next()
+            .expect("concat checks for the end of the pattern");
+        match c {
+            '(' => {
+                self.group_prefix(start)?;
+                let g = self.alternation()?;
+                if !self.eat(")") {
+                    return Err(self.syntax("unclosed group"));
+                }
+                Ok(Item::Other(g))
+            }
+            '[' => Ok(Item::Other(self.class(start)?)),
+            '\\' => match self.escape(start)? {
+                Escaped::Char(c) => Ok(Item::Literal(c)),
+                Escaped::Class(class) => Ok(Item::Other(class_generator(class))),
+            },
+            '^' | '$' => Err(FromRegexError::Anchor { position: start }),
+            '.' => Err(self.unsupported(start, "`.`, which matches any character,")),
+            '*' | '+' | '?' | '{' => Err(FromRegexError::Syntax {
+                position: start,
+                message: format!("'{c}' has nothing to repeat"),
+            }),
+            c => Ok(Item::Literal(c)),
+        }
+    }
+
+    /// Consumes the `?:` or `?<name>` after a group's opening parenthesis, if any.
+    fn group_prefix(&mut self, start: usize) -> Result<(), FromRegexError> {
+        if !self.eat("?") {
+            return Ok(());
+        }
+        if self.eat(":") {
+            return Ok(());
+        }
+        if ["=", "!", "<=", "<!"].iter().any(|prefix| self.eat(prefix)) {
+            return Err(FromRegexError::Lookaround { position: start });
+        }
+        if self.eat("P<") || self.eat("<") {
+            let name_len = self.pattern[self.pos..]
+                .find('>')
+                .ok_or_else(|| self.syntax("unclosed group name"))?;
+            self.pos += name_len + 1;
+            return Ok(());
+        }
+        Err(self.unsupported(start, "this group syntax"))
+    }
+
+    /// A bracketed character class, after its opening `[`.
+    fn class(&mut self, start: usize) -> Result<Generator, FromRegexError> {
+        if self.peek() == Some('^') {
+            return Err(self.unsupported(start, "a negated character class"));
+        }
+
+        let mut class = CharClass::new();
+        let mut first = true;
+        loop {
+            let item_start = self.pos;
+            let c = match self.next() {
+                None => return Err(self.syntax("unclosed character class")),
+                // A ']' at the start of a class is literal
+                Some(']') if !first => break,
+                Some('[') if self.peek() == Some(':') => {
+                    return Err(self.unsupported(item_start, "a POSIX character class"))
+                }
+                Some('\\') => match self.escape(item_start)? {
+                    Escaped::Char(c) => c,
+                    Escaped::Class(escaped) => {
+                        class = class.union(&escaped);
+                        first = false;
+                        continue;
+                    }
+                },
+                Some(c) => c,
+            };
+            first = false;
+
+            // A '-' at the end of a class is literal
+            if self.peek() == Some('-') && !self.pattern[self.pos..].starts_with("-]") {
+                self.next();
+                let end_start = self.pos;
+                let end = match self.next() {
+                    None => return Err(self.syntax("unclosed character class")),
+                    Some('\\') => match self.escape(end_start)? {
+                        Escaped::Char(end) => end,
+                        Escaped::Class(_) => {
+                            return Err(FromRegexError::Syntax {
+                                position: end_start,
+                                message: "a range can't end with a class".into(),
+                            })
+                        }
+                    },
+                    Some(end) => end,
+                };
+                if c > end {
+                    return Err(FromRegexError::Syntax {
+                        position: item_start,
+                        message: format!("the range {c}-{end} is reversed"),
+                    });
+                }
+                class = class.union(&(c..=end).into());
+            } else {
+                class = class.union(&(c..=c).into());
+            }
+        }
+
+        if class.is_empty() {
+            return Err(self.syntax("empty character class"));
+        }
+        Ok(class_generator(class))
+    }
+
+    /// An escape sequence, after its backslash.
+    fn escape(&mut self, start: usize) -> Result<Escaped, FromRegexError> {
+        let c = self
+            .next()
+            .ok_or_else(|| self.syntax("incomplete escape sequence"))?;
+        let escaped = match c {
+            'd' => Escaped::Class(('0'..='9').collect()),
+            'w' => Escaped::Class(
+                ('a'..='z')
+                    .chain('A'..='Z')
+                    .chain('0'..='9')
+                    .chain(['_'])
+                    .collect(),
+            ),
+            's' => Escaped::Class(
+                [' ', '\t', '\n', '\r', '\x0B', '\x0C']
+                    .into_iter()
+                    .collect(),
+            ),
+            'D' | 'W' | 'S' => return Err(self.unsupported(start, "a negated character class")),
+            'b' | 'B' | 'A' | 'z' | 'Z' | 'G' => {
+                return Err(FromRegexError::Anchor { position: start })
+            }
+            '1'..='9' | 'k' => return Err(self.unsupported(start, "a backreference")),
+            'n' => Escaped::Char('\n'),
+            't' => Escaped::Char('\t'),
+            'r' => Escaped::Char('\r'),
+            'f' => Escaped::Char('\x0C'),
+            'v' => Escaped::Char('\x0B'),
+            '0' => Escaped::Char('\0'),
+            'x' => Escaped::Char(self.hex_escape(start, 2)?),
+            'u' => Escaped::Char(self.hex_escape(start, 4)?),
+            c if c.is_ascii_alphanumeric() => {
+                return Err(self.unsupported(start, &format!("the escape sequence \\{c}")))
+            }
+            // Escaped punctuation is literal
+            c => Escaped::Char(c),
+        };
+        Ok(escaped)
+    }
+
+    /// The code point of a `\xHH`, `\uHHHH`, `\x{H...}`, or `\u{H...}` escape, after its `x` or
+    /// `u`. Without braces, the escape has `len` digits.
+    fn hex_escape(&mut self, start: usize, len: usize) -> Result<char, FromRegexError> {
+        let digits = if self.eat("{") {
+            let braced = self.pattern[self.pos..]
+                .find('}')
+                .ok_or_else(|| self.syntax("unclosed hex escape"))?;
+            let digits = &self.pattern[self.pos..self.pos + braced];
+            self.pos += braced + 1;
+            digits
+        } else {
+            let digits = self
+                .pattern
+                .get(self.pos..self.pos + len)
+                .ok_or_else(|| self.syntax("incomplete hex escape"))?;
+            self.pos += len;
+            digits
+        };
+
+        u32::from_str_radix(digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(FromRegexError::Syntax {
+                position: start,
+                message: format!("invalid hex escape {digits:?}"),
+            })
+    }
+}
+
+/// The result of an escape sequence.
+enum Escaped {
+    Char(char),
+    Class(CharClass),
+}
+
+/// Converts a sequence item to a `Generator` so that it can be quantified.
+fn into_generator(item: Item) -> Generator {
+    match item {
+        Item::Literal(c) => Generator::Char(c),
+        Item::Other(g) => g,
+    }
+}
+
+/// Takes the pending `literal`, if any, as a [`Generator::Char`] or [`Generator::Str`].
+fn literal_generator(literal: &mut String) -> Option<Generator> {
+    let mut chars = literal.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => None,
+        (Some(c), None) => {
+            literal.clear();
+            Some(Generator::Char(c))
+        }
+        _ => Some(Generator::Str(std::mem::take(literal))),
+    }
+}
+
+/// The simplest `Generator` producing each character of `class`, preferring the built-in classes.
+fn class_generator(class: CharClass) -> Generator {
+    use Generator::*;
+    let builtin = [
+        AlphaLower,
+        AlphaUpper,
+        Digit,
+        AlphaNumLower,
+        AlphaNumUpper,
+        HexUpper,
+        HexLower,
+        Base64,
+        Base64Url,
+        Base32,
+        Base58,
+        PrintableAscii,
+        Punctuation,
+        Whitespace,
+    ]
+    .into_iter()
+    .find(|g| {
+        let alphabet = g.alphabet().expect("built-in classes have alphabets");
+        alphabet
+            .iter()
+            .map(|c| char::from(*c))
+            .collect::<crate::CharClass>()
+            == class
+    });
+    if let Some(g) = builtin {
+        return g;
+    }
+
+    match class.len() {
+        1 => Char(class.get(0).expect("the class has a character")),
+        _ => CharClass(class),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FromRegexError;
+    use crate::{gen, oneof, Generator};
+
+    fn values(pattern: &str) -> Vec<String> {
+        Generator::from_regex(pattern)
+            .unwrap()
+            .generate_all()
+            .collect()
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(Generator::from_regex("").unwrap(), Generator::Empty);
+        assert_eq!(Generator::from_regex("a").unwrap(), gen!('a'));
+        assert_eq!(Generator::from_regex("foo").unwrap(), gen!("foo"));
+        assert_eq!(Generator::from_regex(r"a\.b\\").unwrap(), gen!(r"a.b\"));
+        assert_eq!(
+            Generator::from_regex(r"\x41é\u{1F30E}").unwrap(),
+            gen!("Aé🌎")
+        );
+        assert_eq!(Generator::from_regex("été").unwrap(), gen!("été"));
+
+        // Quantifiers only apply to the last character of a run of literals
+        assert_eq!(
+            Generator::from_regex("foo?").unwrap(),
+            gen!("fo") + gen!('o').optional()
+        );
+    }
+
+    #[test]
+    fn classes() {
+        assert_eq!(
+            Generator::from_regex("[a-z]").unwrap(),
+            Generator::AlphaLower
+        );
+        assert_eq!(
+            Generator::from_regex("[A-Z]").unwrap(),
+            Generator::AlphaUpper
+        );
+        assert_eq!(Generator::from_regex(r"\d").unwrap(), Generator::Digit);
+        assert_eq!(Generator::from_regex("[0-9]").unwrap(), Generator::Digit);
+        assert_eq!(
+            Generator::from_regex("[0-9a-z]").unwrap(),
+            Generator::AlphaNumLower
+        );
+        assert_eq!(
+            Generator::from_regex(r"[A-Z\d]").unwrap(),
+            Generator::AlphaNumUpper
+        );
+        assert_eq!(
+            Generator::from_regex("[a-f0-9]").unwrap(),
+            Generator::HexLower
+        );
+        assert_eq!(
+            Generator::from_regex("[0-9A-F]").unwrap(),
+            Generator::HexUpper
+        );
+
+        assert_eq!(
+            Generator::from_regex("[A-Za-z0-9+/]").unwrap(),
+            Generator::Base64
+        );
+        assert_eq!(Generator::from_regex(r"\s").unwrap(), Generator::Whitespace);
+        assert_eq!(Generator::from_regex("[x]").unwrap(), gen!('x'));
+        assert_eq!(
+            Generator::from_regex("[a-cxz]").unwrap(),
+            Generator::class("a-cxz")
+        );
+        assert_eq!(
+            Generator::from_regex(r"[\x{10000}-\x{10FFFF}]")
+                .unwrap()
+                .len(),
+            0x100000
+        );
+        assert_eq!(values("[-a]"), ["-", "a"]);
+        assert_eq!(values("[a-]"), ["-", "a"]);
+        assert_eq!(values("[]a]"), ["]", "a"]);
+        assert_eq!(values(r"[\]\-]"), ["-", "]"]);
+        assert_eq!(Generator::from_regex(r"\w").unwrap().len(), 63);
+        assert_eq!(Generator::from_regex(r"[\s_]").unwrap().len(), 7);
+    }
+
+    #[test]
+    fn structure() {
+        assert_eq!(
+            values("iris( (versicolor|virginica|setosa))?"),
+            ["iris", "iris versicolor", "iris virginica", "iris setosa"]
+        );
+        assert_eq!(values("(?:a|bc){2}"), ["aa", "abc", "bca", "bcbc"]);
+        assert_eq!(values("x{1,3}"), ["x", "xx", "xxx"]);
+        assert_eq!(values("x{0,2}"), ["", "x", "xx"]);
+        assert_eq!(values("(a|)b"), ["b", "ab"]);
+        assert_eq!(values("(?P<first>a)(?<second>b)"), ["ab"]);
+        assert_eq!(values("a{2}{2}"), ["aaaa"]);
+        assert_eq!(values("ab|c"), ["ab", "c"]);
+
+        // Lazy quantifiers match the same strings
+        assert_eq!(values("a{1,2}?"), ["a", "aa"]);
+        assert_eq!(values("ba??"), ["b", "ba"]);
+        assert_eq!(values("a{2}?b"), ["aab"]);
+
+        // Empty alternatives only add the empty string once
+        assert_eq!(values("(|)"), [""]);
+        assert_eq!(values("(a||)"), ["", "a"]);
+        assert_eq!(values("(a?|)"), ["", "a"]);
+        assert_eq!(values("(a{0,1}|b|)"), ["", "a", "b"]);
+
+        let g = Generator::from_regex(r"[a-z]{3,8}@(example|test)\.(com|org)").unwrap();
+        assert_eq!(
+            g,
+            Generator::AlphaLower * (3, 8)
+                + '@'
+                + oneof!("example", "test")
+                + '.'
+                + oneof!("com", "org")
+        );
+    }
+
+    #[test]
+    fn errors() {
+        use FromRegexError::*;
+        let err = |pattern| Generator::from_regex(pattern).unwrap_err();
+
+        assert_eq!(err("a*"), Unbounded { position: 1 });
+        assert_eq!(err("(ab)+"), Unbounded { position: 4 });
+        assert_eq!(err("a{2,}"), Unbounded { position: 1 });
+        assert_eq!(err("^abc"), Anchor { position: 0 });
+        assert_eq!(err("abc$"), Anchor { position: 3 });
+        assert_eq!(err(r"\bfoo"), Anchor { position: 0 });
+        assert_eq!(err("foo(?=bar)"), Lookaround { position: 3 });
+        assert_eq!(err("(?<!x)y"), Lookaround { position: 0 });
+
+        assert!(matches!(err("a.c"), Unsupported { position: 1, .. }));
+        assert!(matches!(err("[^a]"), Unsupported { position: 0, .. }));
+        assert!(matches!(err(r"(a)\1"), Unsupported { position: 3, .. }));
+        assert!(matches!(err("(?i)a"), Unsupported { position: 0, .. }));
+        assert!(matches!(err(r"\p{L}"), Unsupported { position: 0, .. }));
+        assert!(matches!(err("a{2}+"), Unsupported { position: 4, .. }));
+        assert!(matches!(err("a?+b"), Unsupported { position: 2, .. }));
+
+        assert!(matches!(err("(ab"), Syntax { position: 3, .. }));
+        assert!(matches!(err("ab)"), Syntax { position: 2, .. }));
+        assert!(matches!(err("[ab"), Syntax { .. }));
+        assert!(matches!(err("[z-a]"), Syntax { position: 1, .. }));
+        assert!(matches!(err("a{3,2}"), Syntax { position: 1, .. }));
+        assert!(matches!(err("a{x}"), Syntax { .. }));
+        assert!(matches!(err("?a"), Syntax { position: 0, .. }));
+        assert!(matches!(err("a|*"), Syntax { position: 2, .. }));
+        assert!(matches!(err(r"\x4"), Syntax { .. }));
+
+        assert_eq!(
+            err("a+").to_string(),
+            "unbounded repetition at 1; use a bounded repetition like {m,n} instead"
+        );
+    }
+}
@@ -1,6 +1,20 @@
 #![allow(non_camel_case_types)]
+use crate::automaton::Automaton;
+use crate::char_class::CharClass;
+use crate::datetime::DateTimeRange;
+use crate::error::CardinalityError;
+use crate::filter::Filter;
+use crate::index::Index;
+use crate::int_range::IntRange;
+#[cfg(feature = "bigint")]
+use crate::iter::BigStringIter;
 use crate::iter::StringIter;
-use crate::transformfn::TransformFn;
+use crate::transformfn::{TransformContext, TransformFn};
+use crate::visit_iter::VisitIter;
+#[cfg(doc)]
+use crate::Visit;
+#[cfg(feature = "bigint")]
+use num_bigint::BigUint;
 use std::{
     fmt::Display,
     mem,
@@ -29,7 +43,8 @@ use std::{
 /// let foo_or_bar_x2 = foo_or_bar.clone() * 2; // generates `foofoo`, `foobar`, `barfoo`, `barbar`
 /// let foo_x2_to_x4 = foo.clone() * (2, 4); // generates `foofoo`, `foofoofoo`, `foofoofoofoo`
 /// ```
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Generator {
     // Some convenience 'constants':
     /// Lowercase ASCII letters (a-z)
@@ -53,6 +68,45 @@ pub enum Generator {
     /// Lowercase hexadecimal values (0-9a-f)
     HexLower,
 
+    /// The standard Base64 alphabet (A-Za-z0-9+/), in order of value
+    Base64,
+
+    /// The URL- and filename-safe Base64 alphabet (A-Za-z0-9-_), in order of value
+    Base64Url,
+
+    /// The RFC 4648 Base32 alphabet (A-Z2-7), in order of value
+    Base32,
+
+    /// The Bitcoin Base58 alphabet, which leaves out 0, O, I, and l, in order of value
+    Base58,
+
+    /// Printable ASCII characters, from space to `~`
+    PrintableAscii,
+
+    /// ASCII punctuation and symbols, as in the POSIX class `[:punct:]`
+    Punctuation,
+
+    /// ASCII whitespace (tab, newline, vertical tab, form feed, carriage return, and space), as in
+    /// the POSIX class `[:space:]`
+    Whitespace,
+
+    /// Any one character of a [`CharClass`], in code point order.
+    ///
+    /// As a regex, this would be, eg, `[a-fxz]`
+    CharClass(CharClass),
+
+    /// Each integer or fixed-precision decimal of an [`IntRange`] exactly once, in ascending
+    /// order.
+    ///
+    /// As a regex, this would be, eg, `[1-9]\d{2}` for the range 100 to 999
+    IntRange(IntRange),
+
+    /// Each date or time of a [`DateTimeRange`] exactly once, in ascending order.
+    ///
+    /// As a regex, this would be, eg, `20(19|2[0-4])-(0[1-9]|1[0-2])-(0[1-9]|[12]\d|3[01])`,
+    /// though that also matches impossible dates
+    DateTime(DateTimeRange),
+
     /// Generates a [`char`] literal.
     Char(char),
 
@@ -85,162 +139,206 @@ pub enum Generator {
     /// As a regex, this would be, eg, `abc`
     Sequence(Vec<Generator>),
 
-    /// Wrap the current generator in a user-defined transformation.
+    /// Wrap the current generator in a user-defined or [built-in](crate::BuiltinTransform)
+    /// transformation.
     Transform {
         inner: Box<Generator>,
         transform_fn: TransformFn,
     },
 
+    /// The values of a generator that match a predicate, created by [`filter`](Self::filter).
+    ///
+    /// The predicate is a closure, so serializing a filter is an error.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Filter(Filter),
+
+    /// The values accepted by an [`Automaton`], created by [`difference`](Self::difference) and
+    /// [`intersection`](Self::intersection).
+    Automaton(Automaton),
+
     /// Doesn't generate anything
+    #[default]
     Empty,
 }
 
 impl Generator {
-    const ASCII_LOWER_A: u8 = 97;
-    const ASCII_UPPER_A: u8 = 65;
-    const ASCII_0: u8 = 48;
-
-    /// Create a regular expression that represents the patterns generated.
-    ///
-    /// The result here is currently best-guess. It's not guaranteed valid, correct, idiomatic, etc.
-    pub fn regex(&self) -> String {
+    /// The characters of the built-in character classes, in the order they're generated.
+    pub(crate) fn alphabet(&self) -> Option<&'static [u8]> {
         use Generator::*;
-
-        match self {
-            AlphaLower => "[a-z]".into(),
-            AlphaUpper => "[A-Z]".into(),
-            Digit => "\\d".into(),
-            AlphaNumUpper => "[A-Z\\d]".into(),
-            AlphaNumLower => "[a-z\\d]".into(),
-            HexUpper => "[\\dA-F]".into(),
-            HexLower => "[\\da-f]".into(),
-            Char(c) => match c {
-                &'.' => "\\.".into(),
-                c => String::from(*c),
-            },
-            Str(s) => s.replace('.', "\\."),
-            OneOf { v, is_optional } => {
-                let regexes = v.iter().map(|a| a.regex()).collect::<Vec<_>>();
-                let mut grp = format!("({})", regexes.join("|"));
-                if *is_optional {
-                    grp.push('?');
-                }
-                grp
-            }
-            RepeatedN(a, n) => a.regex() + "{" + &n.to_string() + "}",
-            RepeatedMN(a, m, n) => a.regex() + "{" + &m.to_string() + "," + &n.to_string() + "}",
-            Sequence(v) => {
-                let regexes = v.iter().map(|a| a.regex()).collect::<Vec<_>>();
-                regexes.join("")
-            }
-            Transform {
-                inner,
-                transform_fn: _,
-            } => inner.regex(),
-            Empty => String::new(),
-        }
+        let alphabet: &'static [u8] = match self {
+            AlphaLower => b"abcdefghijklmnopqrstuvwxyz",
+            AlphaUpper => b"ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            Digit => b"0123456789",
+            AlphaNumLower => b"abcdefghijklmnopqrstuvwxyz0123456789",
+            AlphaNumUpper => b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789",
+            HexUpper => b"0123456789ABCDEF",
+            HexLower => b"0123456789abcdef",
+            Base64 => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+            Base64Url => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+            Base32 => b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+            Base58 => b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz",
+            PrintableAscii => b" !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~",
+            Punctuation => b"!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~",
+            Whitespace => b"\t\n\x0B\x0C\r ",
+            _ => return None,
+        };
+        Some(alphabet)
     }
 
     /// The number of possible patterns represented.
+    ///
+    /// Panics if the number of patterns exceeds `u128::MAX`; see [`try_len`](Self::try_len) for a
+    /// fallible version.
     pub fn len(&self) -> u128 {
-        use Generator::*;
-        match self {
-            AlphaLower | AlphaUpper => 26,
-            Digit => 10,
-            AlphaNumUpper | AlphaNumLower => 36,
-            HexUpper | HexLower => 16,
+        match self.try_len() {
+            Ok(len) => len,
+            Err(e) => panic!("{e}"),
+        }
+    }
 
-            Char(_) | Str(_) => 1,
+    /// The number of possible patterns represented, or [`CardinalityError::Overflow`] if that
+    /// number exceeds `u128::MAX`.
+    ///
+    /// ```
+    /// use generator_combinator::{CardinalityError, Generator};
+    /// assert_eq!((Generator::Digit * 38).try_len(), Ok(10u128.pow(38)));
+    /// assert_eq!((Generator::Digit * 39).try_len(), Err(CardinalityError::Overflow));
+    /// ```
+    pub fn try_len(&self) -> Result<u128, CardinalityError> {
+        self.cardinality().ok_or(CardinalityError::Overflow)
+    }
+
+    /// Whether this `Generator` represents no values at all, such as a [`OneOf`](Self::OneOf)
+    /// with no choices.
+    ///
+    /// Note that [`Empty`](Self::Empty) represents exactly one value (the empty string) and so is
+    /// not empty in this sense.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of possible patterns represented, or `None` if that number doesn't fit in `I`.
+    pub(crate) fn cardinality<I: Index>(&self) -> Option<I> {
+        use Generator::*;
+        let len = match self {
+            Char(_) | Str(_) => I::from_u128(1),
+            CharClass(class) => I::from_u128(class.len() as u128),
+            IntRange(range) => I::from_u128(range.span()).checked_add(&I::from_u128(1))?,
+            DateTime(range) => I::from_u128(range.span() + 1),
+            Filter(filter) => I::from_u128(filter.len()),
+            Automaton(automaton) => automaton.len()?,
 
             OneOf { v, is_optional } => {
                 // Optionals add one value (empty/null)
-                v.iter().map(|a| a.len()).sum::<u128>() + if *is_optional { 1 } else { 0 }
+                let mut len = I::from_u128(if *is_optional { 1 } else { 0 });
+                for a in v {
+                    len = len.checked_add(&a.cardinality()?)?;
+                }
+                len
             }
 
             // Repeated variants are like base-x numbers of length n, where x is the number of combinations for a.
             // RepeatedN is easy:
-            RepeatedN(a, n) => a.len().pow(*n as u32),
+            RepeatedN(a, n) => a.cardinality::<I>()?.checked_pow(*n)?,
             // RepeatedMN has to remove the lower 'bits'/'digits'
             RepeatedMN(a, m, n) => {
-                let base = a.len();
-                (*m..=*n).map(|i| base.pow(i as u32)).sum()
+                let base = a.cardinality::<I>()?;
+                let mut len = I::zero();
+                for i in *m..=*n {
+                    len = len.checked_add(&base.checked_pow(i)?)?;
+                }
+                len
             }
 
-            Sequence(v) => v.iter().map(|a| a.len()).product(),
+            Sequence(v) => {
+                let mut len = I::from_u128(1);
+                for a in v {
+                    len = len.checked_mul(&a.cardinality()?)?;
+                }
+                len
+            }
             Transform {
                 inner,
                 transform_fn: _,
-            } => inner.len(),
-            Empty => 1,
+            } => inner.cardinality()?,
+            Empty => I::from_u128(1),
+
+            // Built-in character classes
+            AlphaLower | AlphaUpper | Digit | AlphaNumLower | AlphaNumUpper | HexUpper
+            | HexLower | Base64 | Base64Url | Base32 | Base58 | PrintableAscii | Punctuation
+            | Whitespace => I::from_u128(
+                self.alphabet()
+                    .expect("built-in classes have alphabets")
+                    .len() as u128,
+            ),
+        };
+
+        Some(len)
+    }
+
+    /// The number of possible patterns represented, for use within a `Generator` whose own
+    /// cardinality is already known to fit in `I`.
+    pub(crate) fn len_as<I: Index>(&self) -> I {
+        self.cardinality()
+            .expect("the cardinality of a component fits if its parent's does")
+    }
+
+    /// Decodes the character of a built-in character class or [`CharClass`](Self::CharClass)
+    /// from `num`.
+    fn decode_char<I: Index>(&self, num: &mut I) -> char {
+        if let Generator::CharClass(class) = self {
+            let rank = num.div_rem_small(class.len());
+            return class
+                .get(rank)
+                .expect("rank is less than the class's length");
+        }
+        let alphabet = self.alphabet().expect("only called for character classes");
+        alphabet[num.div_rem_small(alphabet.len())].into()
+    }
+
+    /// Decodes the offset of a value from the start of an [`IntRange`](Self::IntRange),
+    /// [`DateTime`](Self::DateTime) range, or [`Filter`](Self::Filter) from `num`.
+    fn decode_offset<I: Index>(&self, num: &mut I) -> u128 {
+        let (new_num, offset) = num.div_rem(&self.len_as());
+        *num = new_num;
+        offset
+            .to_u128()
+            .expect("offsets are at most the range's span")
+    }
+
+    /// Generates the value of `inner` encoded in `num`, with `transform_fn` applied.
+    fn generate_transformed<I: Index>(
+        inner: &Generator,
+        transform_fn: &TransformFn,
+        num: &mut I,
+    ) -> String {
+        if !transform_fn.is_contextual() {
+            let mut r = String::new();
+            inner.generate_on_top_of(num, &mut r);
+            return transform_fn.apply(r);
         }
+
+        let (_, index) = num.div_rem(&inner.len_as());
+        let mut parts = vec![];
+        inner.visit_exact_inner(num, &mut String::new(), &mut |part| {
+            parts.push(part.to_string())
+        });
+        let context = TransformContext::new(&index, parts);
+        transform_fn.apply_in(context.parts().concat(), &context)
     }
 
     /// Recursively generates the pattern encoded in `num`, appending values to the `result`.
-    fn generate_on_top_of(&self, num: &mut u128, result: &mut String) {
+    fn generate_on_top_of<I: Index>(&self, num: &mut I, result: &mut String) {
         use Generator::*;
 
         match self {
-            AlphaLower => {
-                let i = (*num % 26) as u8;
-                *num /= 26;
-                let c: char = (Self::ASCII_LOWER_A + i).into();
-                result.push(c);
-            }
-            AlphaUpper => {
-                let i = (*num % 26) as u8;
-                *num /= 26;
-                let c: char = (Self::ASCII_UPPER_A + i).into();
-                result.push(c);
+            IntRange(range) => {
+                let n = range.get(self.decode_offset(num));
+                range.write(n, result);
             }
-            Digit => {
-                let i = (*num % 10) as u8;
-                *num /= 10;
-                let c: char = (Self::ASCII_0 + i).into();
-                result.push(c);
-            }
-            AlphaNumUpper => {
-                let i = (*num % 36) as u8;
-                *num /= 36;
-                let c: char = if i < 26 {
-                    Self::ASCII_UPPER_A + i
-                } else {
-                    Self::ASCII_0 + i - 26
-                }
-                .into();
-                result.push(c);
-            }
-            AlphaNumLower => {
-                let i = (*num % 36) as u8;
-                *num /= 36;
-                let c: char = if i < 26 {
-                    Self::ASCII_LOWER_A + i
-                } else {
-                    Self::ASCII_0 + i - 26
-                }
-                .into();
-                result.push(c);
-            }
-            HexUpper => {
-                let i = (*num % 16) as u8;
-                *num /= 16;
-                let c: char = if i < 10 {
-                    Self::ASCII_0 + i
-                } else {
-                    Self::ASCII_UPPER_A + i - 10
-                }
-                .into();
-                result.push(c);
-            }
-            HexLower => {
-                let i = (*num % 16) as u8;
-                *num /= 16;
-                let c: char = if i < 10 {
-                    Self::ASCII_0 + i
-                } else {
-                    Self::ASCII_LOWER_A + i - 10
-                }
-                .into();
-                result.push(c);
+            DateTime(range) => {
+                let t = range.get(self.decode_offset(num));
+                range.write(t, result);
             }
             Char(c) => {
                 result.push(*c);
@@ -249,53 +347,22 @@ impl Generator {
                 result.push_str(s);
             }
             OneOf { v, is_optional } => {
-                let v_len = self.len();
-
-                // Divide out the impact of this OneOf; the remainder can be
-                // used internally and we'll update num for parent recursions.
-                let new_num = *num / v_len;
-                *num %= v_len;
-
-                if *is_optional && *num == 0 {
-                    // use the optional - don't recurse and don't update result
-                } else {
-                    if *is_optional {
-                        *num -= 1;
-                    }
-                    for a in v {
-                        let a_len = a.len() as u128;
-                        if *num < a_len {
-                            a.generate_on_top_of(num, result);
-                            break;
-                        } else {
-                            // subtract out the impact of this OneOf branch
-                            *num -= a_len;
-                        }
-                    }
+                let (branch, new_num) = Self::split_one_of(self.len_as(), v, *is_optional, num);
+                if let Some(a) = branch {
+                    a.generate_on_top_of(num, result);
                 }
-
                 *num = new_num;
             }
             RepeatedN(a, n) => {
                 // Repeat this one exactly n times
-                let mut parts = Vec::with_capacity(*n);
-                for _ in 0..*n {
-                    let mut r = String::new();
-                    a.generate_on_top_of(num, &mut r);
-                    parts.push(r);
-                }
-                parts.reverse();
-                result.push_str(&parts.join(""));
+                Self::for_each_repetition(a, *n, num, |mut i| a.generate_on_top_of(&mut i, result));
             }
-            RepeatedMN(a, m, n) => {
-                let mut parts = Vec::with_capacity(n - m + 1);
-                for _ in *m..=*n {
-                    let mut r = String::new();
-                    a.generate_on_top_of(num, &mut r);
-                    parts.push(r);
-                }
-                parts.reverse();
-                result.push_str(&parts.join(""));
+            RepeatedMN(a, m, _n) => {
+                let (count, new_num) = Self::split_repeated_mn(self.len_as(), a, *m, num);
+                Self::for_each_repetition(a, count, num, |mut i| {
+                    a.generate_on_top_of(&mut i, result)
+                });
+                *num = new_num;
             }
             Sequence(v) => {
                 for a in v {
@@ -305,13 +372,114 @@ impl Generator {
             Transform {
                 inner,
                 transform_fn,
-            } => {
-                let mut r = String::new();
-                inner.generate_on_top_of(num, &mut r);
-                let r = (transform_fn.0)(r);
-                result.push_str(&r);
+            } => result.push_str(&Self::generate_transformed(inner, transform_fn, num)),
+            Filter(filter) => {
+                let index = filter.inner_index(self.decode_offset(num));
+                filter
+                    .inner()
+                    .generate_on_top_of(&mut I::from_u128(index), result);
+            }
+            Automaton(automaton) => {
+                let (new_num, offset) = num.div_rem(&self.len_as());
+                *num = new_num;
+                automaton.write(offset, result);
             }
             Empty => {}
+
+            // Built-in character classes and CharClass
+            AlphaLower | AlphaUpper | Digit | AlphaNumLower | AlphaNumUpper | HexUpper
+            | HexLower | Base64 | Base64Url | Base32 | Base58 | PrintableAscii | Punctuation
+            | Whitespace | CharClass(_) => result.push(self.decode_char(num)),
+        }
+    }
+
+    /// Selects the branch encoded in `num` for `OneOf { v, is_optional }`, whose total length is
+    /// `len`.
+    ///
+    /// On return, `num` holds the index within the selected branch and the returned tuple holds
+    /// that branch (or `None` for the optional empty value) and the value `num` should take for
+    /// parent recursions.
+    fn split_one_of<'g, I: Index>(
+        len: I,
+        v: &'g [Generator],
+        is_optional: bool,
+        num: &mut I,
+    ) -> (Option<&'g Generator>, I) {
+        // Divide out the impact of this OneOf; the remainder can be
+        // used internally and we'll update num for parent recursions.
+        let (new_num, rem) = num.div_rem(&len);
+        *num = rem;
+
+        if is_optional && num.is_zero() {
+            // use the optional - don't recurse and don't update result
+            return (None, new_num);
+        }
+        if is_optional {
+            *num = num.sub(&I::from_u128(1));
+        }
+
+        for a in v {
+            let a_len = a.len_as();
+            if *num < a_len {
+                return (Some(a), new_num);
+            }
+            // subtract out the impact of this OneOf branch
+            *num = num.sub(&a_len);
+        }
+        unreachable!("num is less than the sum of the branch lengths")
+    }
+
+    /// Selects the number of repetitions encoded in `num` for `RepeatedMN(a, m, _)`, whose total
+    /// length is `len`.
+    ///
+    /// The values of a `RepeatedMN` are ordered by repetition count: the first `a.len()^m` values
+    /// repeat `a` exactly `m` times, the next `a.len()^(m+1)` values repeat it `m+1` times, etc.
+    /// On return, `num` holds the index within the selected repetition count and the returned
+    /// tuple holds that count and the value `num` should take for parent recursions.
+    fn split_repeated_mn<I: Index>(len: I, a: &Generator, m: usize, num: &mut I) -> (usize, I) {
+        let (new_num, rem) = num.div_rem(&len);
+        *num = rem;
+
+        let base: I = a.len_as();
+        let mut count = m;
+        loop {
+            let block = base
+                .checked_pow(count)
+                .expect("bounded by the RepeatedMN's length");
+            if *num < block {
+                break;
+            }
+            *num = num.sub(&block);
+            count += 1;
+        }
+
+        (count, new_num)
+    }
+
+    /// Divides `count` repetitions of `a` out of `num`, calling `f` with the index of each
+    /// repetition in the order they appear.
+    ///
+    /// Repetitions are like the digits of a base-`a.len()` number: the first is the most
+    /// significant.
+    fn for_each_repetition<I: Index>(
+        a: &Generator,
+        count: usize,
+        num: &mut I,
+        mut f: impl FnMut(I),
+    ) {
+        let base: I = a.len_as();
+        let place = |exp| {
+            base.checked_pow(exp)
+                .expect("bounded by the repetition's length")
+        };
+
+        let (new_num, mut rem) = num.div_rem(&place(count));
+        *num = new_num;
+
+        for exp in (0..count).rev() {
+            let (i, r) = rem.div_rem(&place(exp));
+            rem = r;
+            f(i);
         }
     }
 
@@ -322,14 +490,34 @@ impl Generator {
         let range = self.len();
         assert!(num < range);
 
-        let mut num = num;
+        self.generate_index(num)
+    }
 
+    /// Generates the [`String`] encoded by `num`, which must be in range.
+    pub(crate) fn generate_index<I: Index>(&self, mut num: I) -> String {
         // build up a single string
         let mut result = String::new();
         self.generate_on_top_of(&mut num, &mut result);
         result
     }
 
+    /// Generates the [`String`] encoded by the specified `num`, or an error if `num` is out of
+    /// range or the range itself exceeds `u128::MAX`.
+    pub fn try_generate_one(&self, num: u128) -> Result<String, CardinalityError> {
+        self.check_index(num)?;
+        Ok(self.generate_index(num))
+    }
+
+    /// Checks that `num` is a valid index into this `Generator`'s values.
+    fn check_index(&self, num: u128) -> Result<(), CardinalityError> {
+        let len = self.try_len()?;
+        if num < len {
+            Ok(())
+        } else {
+            Err(CardinalityError::IndexOutOfRange { index: num, len })
+        }
+    }
+
     /// Makes this `Generator` optional.
     ///
     /// As a regex, this is the `?` operator.
@@ -358,20 +546,115 @@ impl Generator {
     }
 
     /// Provides an iterator across all possible values for this `Generator`.
-    pub fn generate_all(&self) -> StringIter {
+    ///
+    /// Panics if the number of values exceeds `u128::MAX`; see
+    /// [`try_generate_all`](Self::try_generate_all) for a fallible version.
+    pub fn generate_all(&self) -> StringIter<'_> {
+        self.into()
+    }
+
+    /// Provides an iterator across all possible values for this `Generator`, yielding a [`Visit`]
+    /// handle for each that can pass its component parts to a callback, as with
+    /// [`visit_one`](Self::visit_one).
+    ///
+    /// This streams over every value without building each [`String`]:
+    /// ```
+    /// use generator_combinator::{oneof, Generator};
+    /// let g = oneof!("foo", "bar") + Generator::Digit * 2;
+    ///
+    /// let mut scratch = String::new();
+    /// let mut digit_sum = 0;
+    /// for value in g.visit_all() {
+    ///     value.visit_with(&mut scratch, |part| {
+    ///         if let Ok(d) = part.parse::<u32>() {
+    ///             digit_sum += d;
+    ///         }
+    ///     });
+    /// }
+    /// assert_eq!(digit_sum, 2 * 2 * 10 * 45);
+    /// ```
+    pub fn visit_all(&self) -> VisitIter<'_> {
         self.into()
     }
 
-    /// Includes a user-defined transformation when generating values.
-    pub fn transform(self, f: fn(String) -> String) -> Self {
-        let transform_fn = TransformFn(Box::new(f));
+    /// Provides an iterator across all possible values for this `Generator`, or
+    /// [`CardinalityError::Overflow`] if the number of values exceeds `u128::MAX`.
+    pub fn try_generate_all(&self) -> Result<StringIter<'_>, CardinalityError> {
+        Ok(StringIter::new(self, self.try_len()?))
+    }
 
+    /// Includes a transformation when generating values: a closure, a
+    /// [`BuiltinTransform`](crate::BuiltinTransform), or a [`TransformFn`], such as one built from
+    /// an `Arc<dyn Fn(String) -> String + Send + Sync>` shared between generators.
+    ///
+    /// A closure is unnamed, so the result only compares equal to its own clones. Use
+    /// [`transform_named`](Self::transform_named) for generators that should compare equal when
+    /// they apply the same transformation. Its argument's type must be written out, as in
+    /// `|s: String|`, unless it's already known.
+    ///
+    /// Unlike closures, built-in transforms can be rendered by [`regex`](Self::regex), undone by
+    /// [`index_of`](Self::index_of), and serialized with the `serde` feature.
+    ///
+    /// ```
+    /// use generator_combinator::{BuiltinTransform, Generator};
+    /// let g = (Generator::Digit * 2).transform(BuiltinTransform::Prefix("#".into()));
+    /// assert_eq!(g.generate_one(42), "#42");
+    /// assert_eq!(g.regex(), r"#\d{2}");
+    /// assert_eq!(g.index_of("#42"), Some(42));
+    ///
+    /// let g = (Generator::Digit * 2).transform(|s: String| s.replace('0', "o"));
+    /// assert_eq!(g.generate_one(10), "1o");
+    /// ```
+    pub fn transform(self, transform_fn: impl Into<TransformFn>) -> Self {
         Self::Transform {
             inner: Box::new(self),
-            transform_fn,
+            transform_fn: transform_fn.into(),
         }
     }
 
+    /// Includes a user-defined transformation identified by `name` when generating values.
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// let upper = |s: String| s.to_uppercase();
+    /// let a = Generator::AlphaLower.transform_named("upper", upper);
+    /// let b = Generator::AlphaLower.transform_named("upper", upper);
+    /// assert_eq!(a, b);
+    /// assert_ne!(a, Generator::AlphaLower.transform(upper));
+    /// ```
+    pub fn transform_named(
+        self,
+        name: impl Into<String>,
+        f: impl Fn(String) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.transform(TransformFn::named(name, f))
+    }
+
+    /// Includes a user-defined transformation that also receives the [`TransformContext`] of each
+    /// value: its index and the parts that [`visit_one`](Self::visit_one) would produce for it.
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// // Append a check digit: the sum of the digits, mod 10
+    /// let g = (Generator::Digit * 3).transform_with_context(|s, context| {
+    ///     let sum: u32 = context.parts().iter().map(|d| d.parse::<u32>().unwrap()).sum();
+    ///     format!("{s}{}", sum % 10)
+    /// });
+    /// assert_eq!(g.generate_one(478), "4789");
+    ///
+    /// // Number the values
+    /// let g = Generator::AlphaUpper.transform_with_context(|s, context| {
+    ///     format!("{}. {s}", context.index() + 1)
+    /// });
+    /// assert_eq!(g.generate_one(2), "3. C");
+    /// ```
+    pub fn transform_with_context(
+        self,
+        f: impl Fn(String, &TransformContext) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.transform(TransformFn::contextual(f))
+    }
+
     /// For a value specified by `num`, applies the callback `cb` for each of the component values
     /// for this Generator.
     ///
@@ -385,148 +668,169 @@ impl Generator {
         let range = self.len();
         assert!(num < range);
 
-        self.visit_exact_inner(&mut num, &mut cb);
+        self.visit_exact_inner(&mut num, &mut String::new(), &mut |part| {
+            cb(part.to_string())
+        });
     }
 
-    /// Internal function to recursively visit each of the components of this Generator.
-    fn visit_exact_inner<F>(&self, num: &mut u128, cb: &mut F)
+    /// Like [`visit_one`](Self::visit_one), but returns an error instead of panicking if `num` is
+    /// out of range or the range itself exceeds `u128::MAX`.
+    pub fn try_visit_one<F>(&self, mut num: u128, mut cb: F) -> Result<(), CardinalityError>
     where
         F: FnMut(String),
+    {
+        self.check_index(num)?;
+        self.visit_exact_inner(&mut num, &mut String::new(), &mut |part| {
+            cb(part.to_string())
+        });
+        Ok(())
+    }
+
+    /// Internal function to recursively visit each of the components of this Generator.
+    ///
+    /// Parts are borrowed from the generator where possible; repeated values are generated into
+    /// `scratch`, which is cleared and reused for each. Only [`Transform`](Self::Transform) needs
+    /// to allocate.
+    pub(crate) fn visit_exact_inner<I, F>(&self, num: &mut I, scratch: &mut String, cb: &mut F)
+    where
+        I: Index,
+        F: FnMut(&str),
     {
         use Generator::*;
 
         match self {
-            AlphaLower => {
-                let i = (*num % 26) as u8;
-                *num /= 26;
-                let c: char = (Self::ASCII_LOWER_A + i).into();
-                cb(String::from(c));
+            IntRange(range) => {
+                let n = range.get(self.decode_offset(num));
+                scratch.clear();
+                range.write(n, scratch);
+                cb(scratch);
             }
-            AlphaUpper => {
-                let i = (*num % 26) as u8;
-                *num /= 26;
-                let c: char = (Self::ASCII_UPPER_A + i).into();
-                cb(String::from(c));
-            }
-            Digit => {
-                let i = (*num % 10) as u8;
-                *num /= 10;
-                let c: char = (Self::ASCII_0 + i).into();
-                cb(String::from(c));
-            }
-            AlphaNumUpper => {
-                let i = (*num % 36) as u8;
-                *num /= 36;
-                let c: char = if i < 26 {
-                    Self::ASCII_UPPER_A + i
-                } else {
-                    Self::ASCII_0 + i - 26
-                }
-                .into();
-                cb(String::from(c));
+            DateTime(range) => {
+                let t = range.get(self.decode_offset(num));
+                scratch.clear();
+                range.write(t, scratch);
+                cb(scratch);
             }
-            AlphaNumLower => {
-                let i = (*num % 36) as u8;
-                *num /= 36;
-                let c: char = if i < 26 {
-                    Self::ASCII_LOWER_A + i
-                } else {
-                    Self::ASCII_0 + i - 26
-                }
-                .into();
-                cb(String::from(c));
-            }
-            HexUpper => {
-                let i = (*num % 16) as u8;
-                *num /= 16;
-                let c: char = if i < 10 {
-                    Self::ASCII_0 + i
-                } else {
-                    Self::ASCII_UPPER_A + i - 10
-                }
-                .into();
-                cb(String::from(c));
-            }
-            HexLower => {
-                let i = (*num % 16) as u8;
-                *num /= 16;
-                let c: char = if i < 10 {
-                    Self::ASCII_0 + i
-                } else {
-                    Self::ASCII_LOWER_A + i - 10
-                }
-                .into();
-                cb(String::from(c));
-            }
-            Char(c) => cb(String::from(*c)),
-            Str(s) => cb(s.to_string()),
+            Char(c) => cb(c.encode_utf8(&mut [0; 4])),
+            Str(s) => cb(s),
             OneOf { v, is_optional } => {
-                let v_len = self.len();
-
-                // Divide out the impact of this OneOf; the remainder can be
-                // used internally and we'll update num for parent recursions.
-                let new_num = *num / v_len;
-                *num %= v_len;
-
-                if *is_optional && *num == 0 {
-                    // use the optional - don't recurse and don't update result
-                } else {
-                    if *is_optional {
-                        *num -= 1;
-                    }
-                    for a in v {
-                        let a_len = a.len();
-                        if *num < a_len {
-                            a.visit_exact_inner(num, cb);
-                            break;
-                        } else {
-                            // subtract out the impact of this OneOf branch
-                            *num -= a_len;
-                        }
-                    }
+                let (branch, new_num) = Self::split_one_of(self.len_as(), v, *is_optional, num);
+                if let Some(a) = branch {
+                    a.visit_exact_inner(num, scratch, cb);
                 }
-
                 *num = new_num;
             }
             RepeatedN(a, n) => {
                 // Repeat this one exactly n times
-                let mut parts = Vec::with_capacity(*n);
-                for _ in 0..*n {
-                    let mut r = String::new();
-                    a.generate_on_top_of(num, &mut r);
-                    parts.push(r);
-                }
-
-                parts.into_iter().rev().for_each(cb);
+                Self::for_each_repetition(a, *n, num, |mut i| {
+                    scratch.clear();
+                    a.generate_on_top_of(&mut i, scratch);
+                    cb(scratch);
+                });
             }
-            RepeatedMN(a, m, n) => {
-                let mut parts = Vec::with_capacity(n - m + 1);
-                for _ in *m..=*n {
-                    let mut r = String::new();
-                    a.generate_on_top_of(num, &mut r);
-                    parts.push(r);
-                }
-                parts.into_iter().rev().for_each(cb);
+            RepeatedMN(a, m, _n) => {
+                let (count, new_num) = Self::split_repeated_mn(self.len_as(), a, *m, num);
+                Self::for_each_repetition(a, count, num, |mut i| {
+                    scratch.clear();
+                    a.generate_on_top_of(&mut i, scratch);
+                    cb(scratch);
+                });
+                *num = new_num;
             }
-            Sequence(v) => v.iter().for_each(|a| a.visit_exact_inner(num, cb)),
+            Sequence(v) => v.iter().for_each(|a| a.visit_exact_inner(num, scratch, cb)),
             Transform {
                 inner,
                 transform_fn,
-            } => {
-                let mut r = String::new();
-                inner.generate_on_top_of(num, &mut r);
-                let r = (transform_fn.0)(r);
-                cb(r);
+            } => cb(&Self::generate_transformed(inner, transform_fn, num)),
+            Filter(filter) => {
+                let index = filter.inner_index(self.decode_offset(num));
+                filter
+                    .inner()
+                    .visit_exact_inner(&mut I::from_u128(index), scratch, cb);
+            }
+            Automaton(automaton) => {
+                let (new_num, offset) = num.div_rem(&self.len_as());
+                *num = new_num;
+                scratch.clear();
+                automaton.write(offset, scratch);
+                cb(scratch);
             }
             // Empty won't invoke the callback
             Empty => {}
+
+            // Built-in character classes and CharClass
+            AlphaLower | AlphaUpper | Digit | AlphaNumLower | AlphaNumUpper | HexUpper
+            | HexLower | Base64 | Base64Url | Base32 | Base58 | PrintableAscii | Punctuation
+            | Whitespace | CharClass(_) => cb(self.decode_char(num).encode_utf8(&mut [0; 4])),
         }
     }
 }
 
-impl Default for Generator {
-    fn default() -> Self {
-        Generator::Empty
+/// Arbitrary-precision counterparts of the `u128` functions, for generators whose number of
+/// values exceeds `u128::MAX`.
+///
+/// ```
+/// use generator_combinator::{BigUint, Generator};
+/// let token = Generator::AlphaNumLower * 40;
+/// assert!(token.try_len().is_err());
+/// assert_eq!(token.len_big(), BigUint::from(36u8).pow(40));
+///
+/// let last = token.len_big() - 1u8;
+/// assert_eq!(token.generate_one_big(&last), "9".repeat(40));
+/// ```
+#[cfg(feature = "bigint")]
+impl Generator {
+    /// The number of possible patterns represented.
+    pub fn len_big(&self) -> BigUint {
+        self.cardinality()
+            .expect("BigUint cardinality can't overflow")
+    }
+
+    /// Generates the [`String`] encoded by the specified `num`.
+    ///
+    /// Panics if `num` exceeds the length given by [Generator::len_big]
+    pub fn generate_one_big(&self, num: &BigUint) -> String {
+        self.generate_index_big(num, &self.len_big())
+    }
+
+    /// Generates the [`String`] encoded by `num`, given this `Generator`'s length `len`.
+    ///
+    /// Panics if `num` isn't less than `len`.
+    pub(crate) fn generate_index_big(&self, num: &BigUint, len: &BigUint) -> String {
+        assert!(num < len, "num is out of range");
+
+        // Use native arithmetic when the range fits
+        match u128::try_from(num) {
+            Ok(num) if u128::try_from(len).is_ok() => self.generate_index(num),
+            _ => self.generate_index(num.clone()),
+        }
+    }
+
+    /// For a value specified by `num`, applies the callback `cb` for each of the component values
+    /// for this Generator. See [`visit_one`](Self::visit_one).
+    ///
+    /// Panics if `num` exceeds the length given by [Generator::len_big]
+    pub fn visit_one_big<F>(&self, num: &BigUint, mut cb: F)
+    where
+        F: FnMut(String),
+    {
+        let len = self.len_big();
+        assert!(num < &len, "num is out of range");
+
+        let mut cb = |part: &str| cb(part.to_string());
+        // Use native arithmetic when the range fits
+        match u128::try_from(num) {
+            Ok(mut num) if u128::try_from(&len).is_ok() => {
+                self.visit_exact_inner(&mut num, &mut String::new(), &mut cb)
+            }
+            _ => self.visit_exact_inner(&mut num.clone(), &mut String::new(), &mut cb),
+        }
+    }
+
+    /// Provides an iterator across all possible values for this `Generator`, however many there
+    /// are.
+    pub fn generate_all_big(&self) -> BigStringIter<'_> {
+        self.into()
     }
 }
 
@@ -652,7 +956,10 @@ impl Mul<(usize, usize)> for Generator {
         assert!(m <= n);
 
         let lhs = Box::new(self);
-        if m == 0 {
+        if n == 0 {
+            // Repeating a generator zero times transforms it to Empty
+            Generator::Empty
+        } else if m == 0 {
             // if the lower bound is zero, then this is an optional pattern
             Generator::RepeatedMN(lhs, 1, n).optional()
         } else {
@@ -668,7 +975,10 @@ impl MulAssign<(usize, usize)> for Generator {
 
         let lhs = mem::take(self);
 
-        *self = if m == 0 {
+        *self = if n == 0 {
+            // Repeating a generator zero times transforms it to Empty
+            Generator::Empty
+        } else if m == 0 {
             Generator::RepeatedMN(Box::new(lhs), 1, n).optional()
         } else {
             Generator::RepeatedMN(Box::new(lhs), m, n)
@@ -935,7 +1245,7 @@ mod tests {
 
         // Same thing but with postprocessing
         let onetwothree = (Generator::Digit * 10)
-            .transform(|s| s.trim_start_matches('0').to_string())
+            .transform(|s: String| s.trim_start_matches('0').to_string())
             .generate_one(123);
         assert_eq!(onetwothree, "123");
     }
@@ -950,6 +1260,42 @@ mod tests {
         assert_eq!(hex.generate_one(464_375_821), "0x1BADD00D");
     }
 
+    #[test]
+    fn encoding_alphabets() {
+        use Generator::*;
+        for (g, len) in [
+            (Base64, 64),
+            (Base64Url, 64),
+            (Base32, 32),
+            (Base58, 58),
+            (PrintableAscii, 95),
+            (Punctuation, 32),
+            (Whitespace, 6),
+        ] {
+            assert_eq!(g.len(), len);
+            let mut values: Vec<_> = g.generate_all().collect();
+            values.dedup();
+            assert_eq!(values.len() as u128, len);
+        }
+
+        // Values are in the order of the digits they encode
+        let g = Base64 * 4;
+        assert_eq!(g.generate_one(0), "AAAA");
+        assert_eq!(g.generate_one(0x4D616E), "TWFu");
+        assert_eq!(g.generate_one(0xFFFFFF), "////");
+        assert_eq!((Base64Url * 2).generate_one(0xFFF), "__");
+        assert_eq!((Base32 * 2).generate_one(31 * 32 + 26), "72");
+        assert_eq!((Base58 * 2).generate_one(57), "1z");
+
+        assert!(PrintableAscii
+            .generate_all()
+            .all(|s| s.bytes().all(|b| b.is_ascii_graphic() || b == b' ')));
+        assert!(Punctuation
+            .generate_all()
+            .all(|s| s.bytes().all(|b| b.is_ascii_punctuation())));
+        assert!(Whitespace.generate_all().all(|s| s.trim().is_empty()));
+    }
+
     #[test]
     fn simplify() {
         let foo_opt1 = gen!("foo").optional();
@@ -1026,7 +1372,7 @@ mod tests {
         let foobarbaz = oneof!("foo", "bar", "baz");
 
         // Trim any leading 'b' from (foo|bar|baz)
-        let fooaraz = foobarbaz.clone().transform(|s| {
+        let fooaraz = foobarbaz.clone().transform(|s: String| {
             if s.starts_with("b") {
                 s.trim_start_matches('b').to_string()
             } else {
@@ -1040,21 +1386,49 @@ mod tests {
         assert_eq!("az", fooaraz.generate_one(2));
 
         // Uppercase (foo|bar|baz)
-        let foobarbaz_upper = foobarbaz.clone().transform(|s| s.to_uppercase());
+        let foobarbaz_upper = foobarbaz.clone().transform(|s: String| s.to_uppercase());
         assert_eq!(3, foobarbaz_upper.len());
         assert_eq!("FOO", foobarbaz_upper.generate_one(0));
         assert_eq!("BAR", foobarbaz_upper.generate_one(1));
         assert_eq!("BAZ", foobarbaz_upper.generate_one(2));
+        assert_ne!(foobarbaz_upper, fooaraz);
+        assert_eq!(foobarbaz_upper, foobarbaz_upper.clone());
+
+        // Transforms may capture their environment
+        let suffix = String::from("!");
+        let exclaim = foobarbaz.clone().transform(move |s: String| s + &suffix);
+        assert_eq!("bar!", exclaim.generate_one(1));
 
         let ten_digits = Generator::Digit * 10;
         let onetwothree = ten_digits.generate_one(123);
         assert_eq!(onetwothree, "0000000123");
         let onetwothree = ten_digits
-            .transform(|s| s.trim_start_matches('0').to_string())
+            .transform(|s: String| s.trim_start_matches('0').to_string())
             .generate_one(123);
         assert_eq!(onetwothree, "123");
     }
 
+    #[test]
+    fn test_transform_with_context() {
+        // Each transformed value sees its index within the transformed generator, not the whole
+        let tagged = (oneof!("ab", "c") + Generator::Digit).transform_with_context(|s, context| {
+            format!("{s}:{}:{}", context.index(), context.parts().join("/"))
+        });
+        let g = gen!("x") + tagged.clone() + oneof!("-", "+");
+        assert_eq!(g.len(), 40);
+        assert_eq!(g.generate_one(0), "xab0:0:ab/0-");
+        assert_eq!(g.generate_one(23), "xc1:3:c/1+");
+        assert_eq!(g.generate_one(39), "xc9:19:c/9+");
+
+        let mut parts = vec![];
+        g.visit_one(39, |part| parts.push(part));
+        assert_eq!(parts, vec!["x", "c9:19:c/9", "+"]);
+
+        // Contextual transforms are opaque
+        assert_eq!(tagged.index_of("ab0:0:ab/0"), None);
+        assert_ne!(tagged, tagged.clone().transform_with_context(|s, _| s));
+    }
+
     #[test]
     fn test_visit() {
         let foobarbaz = oneof!("foo", "bar", "baz");
@@ -1075,7 +1449,7 @@ mod tests {
         assert_eq!("(foo|bar|baz)\\d{4}", fbb_nnnn.regex());
 
         let hi45 = Generator::from("hi") * (4, 5);
-        assert_eq!("hi{4,5}", hi45.regex());
+        assert_eq!("(hi){4,5}", hi45.regex());
 
         let sea = Generator::from("Seattle") + gen!(", WA").optional();
         assert_eq!("Seattle(, WA)?", sea.regex());
@@ -1087,7 +1461,7 @@ mod tests {
             const RANGE : u128 = 809_190_000;
 
             let space = Generator::from(' ');
-            let number = (Generator::Digit * (3, 5)).transform(|s| s.trim_start_matches('0').to_string());
+            let number = (Generator::Digit * (3, 5)).transform(|s: String| s.trim_start_matches('0').to_string());
 
             let directional = space.clone() + oneof!("N", "E", "S", "W", "NE", "SE", "SW", "NW");
             let street_names = space.clone() + oneof!("Boren", "Olive", "Spring", "Cherry", "Seneca", "Yesler", "Madison", "James", "Union", "Mercer");
@@ -1125,6 +1499,93 @@ mod tests {
         let _n = g.len();
     }
 
+    #[test]
+    fn checked_cardinality() {
+        let g = Generator::Digit * 39;
+        assert_eq!(g.try_len(), Err(CardinalityError::Overflow));
+        assert_eq!(g.try_generate_one(0), Err(CardinalityError::Overflow));
+        assert!(g.try_generate_all().is_err());
+
+        // Overflow anywhere in the tree is reported, even if it's nested or summed
+        let d38 = Generator::Digit * 38;
+        let g = d38.clone() | d38.clone() | d38.clone() | d38;
+        assert_eq!(g.try_len(), Err(CardinalityError::Overflow));
+        let g = (Generator::AlphaLower * (27, 28)).optional();
+        assert_eq!(g.try_len(), Err(CardinalityError::Overflow));
+
+        let g = Generator::Digit * 38;
+        assert_eq!(g.try_len(), Ok(10u128.pow(38)));
+        assert_eq!(g.try_generate_one(42), Ok(format!("{:038}", 42)));
+        assert_eq!(
+            g.try_generate_one(10u128.pow(38)),
+            Err(CardinalityError::IndexOutOfRange {
+                index: 10u128.pow(38),
+                len: 10u128.pow(38)
+            })
+        );
+
+        let mut parts = Vec::new();
+        assert_eq!(
+            oneof!("foo", "bar").try_visit_one(2, |part| parts.push(part)),
+            Err(CardinalityError::IndexOutOfRange { index: 2, len: 2 })
+        );
+        assert!(parts.is_empty());
+        assert_eq!(
+            oneof!("foo", "bar").try_visit_one(1, |part| parts.push(part)),
+            Ok(())
+        );
+        assert_eq!(parts, ["bar"]);
+
+        let values: Vec<_> = oneof!("foo", "bar").try_generate_all().unwrap().collect();
+        assert_eq!(values, ["foo", "bar"]);
+    }
+
+    #[test]
+    #[cfg(feature = "bigint")]
+    fn bigint_cardinality() {
+        let g = Generator::Digit * 39;
+        assert_eq!(g.len_big(), BigUint::from(10u8).pow(39));
+
+        let nines = g.len_big() - 1u8;
+        assert_eq!(g.generate_one_big(&nines), "9".repeat(39));
+        assert_eq!(
+            g.generate_one_big(&BigUint::from(42u8)),
+            format!("{:039}", 42)
+        );
+
+        let mut visited = String::new();
+        g.visit_one_big(&nines, |part| visited.push_str(&part));
+        assert_eq!(visited, "9".repeat(39));
+
+        // Mixed-radix decoding carries across components beyond u128
+        let g =
+            oneof!("foo", "bar", "baz") + Generator::HexUpper * 40 + (Generator::Digit * (1, 2));
+        let num = BigUint::from(16u8).pow(40) * 3u8 * 7u8 + 2u8;
+        assert_eq!(g.generate_one_big(&num), format!("baz{}7", "0".repeat(40)));
+
+        let mut values = g.generate_all_big();
+        assert_eq!(values.next(), Some(format!("foo{}0", "0".repeat(40))));
+        assert_eq!(values.next(), Some(format!("bar{}0", "0".repeat(40))));
+    }
+
+    #[test]
+    #[cfg(feature = "bigint")]
+    fn bigint_matches_u128() {
+        let g = oneof!("a", "b") * (1, 3) + Generator::AlphaNumLower * 2 + gen!("x").optional();
+        let values: Vec<_> = g.generate_all().collect();
+        let big_values: Vec<_> = g.generate_all_big().collect();
+        assert_eq!(values, big_values);
+
+        for (i, value) in values.iter().enumerate() {
+            let mut visited = String::new();
+            g.visit_exact_inner(&mut BigUint::from(i), &mut String::new(), &mut |part| {
+                visited.push_str(part)
+            });
+            assert_eq!(&visited, value);
+            assert_eq!(&g.generate_index(BigUint::from(i)), value);
+        }
+    }
+
     #[test]
     fn lower_limit_0() {
         // Both should be: empty, a, aa
@@ -1133,12 +1594,97 @@ mod tests {
         assert_eq!(g1.len(), g2.len());
         assert_eq!(g1, g2);
 
-        let g1 = (Generator::Char('a') * (1, 2)) * 0;
+        // Through `Mul::mul`, since clippy rejects the operator with a literal 0
+        let g1 = Mul::mul(Generator::Char('a') * (1, 2), 0);
         let g2 = Generator::Empty;
         assert_eq!(g1.len(), g2.len());
         assert_eq!(g1, g2);
     }
 
+    /// Brute-force expansion of every string a `Generator` represents, one entry per index.
+    fn expand(g: &Generator) -> Vec<String> {
+        use Generator::*;
+        match g {
+            OneOf { v, is_optional } => {
+                let mut values = if *is_optional {
+                    vec![String::new()]
+                } else {
+                    vec![]
+                };
+                values.extend(v.iter().flat_map(expand));
+                values
+            }
+            RepeatedN(a, n) => expand(&Sequence(vec![(**a).clone(); *n])),
+            RepeatedMN(a, m, n) => (*m..=*n)
+                .flat_map(|count| expand(&Sequence(vec![(**a).clone(); count])))
+                .collect(),
+            Sequence(v) => v.iter().fold(vec![String::new()], |prefixes, a| {
+                let suffixes = expand(a);
+                prefixes
+                    .iter()
+                    .flat_map(|p| suffixes.iter().map(move |s| format!("{p}{s}")))
+                    .collect()
+            }),
+            Transform {
+                inner,
+                transform_fn,
+            } => expand(inner)
+                .into_iter()
+                .map(|s| transform_fn.apply(s))
+                .collect(),
+            _ => (0..g.len()).map(|i| g.generate_one(i)).collect(),
+        }
+    }
+
+    /// Checks that `generate_all` produces exactly the brute-force expansion of `g`.
+    fn assert_matches_expansion(g: &Generator) {
+        let mut expected = expand(g);
+        assert_eq!(expected.len() as u128, g.len());
+
+        let mut generated: Vec<_> = g.generate_all().collect();
+        for (i, s) in generated.iter().enumerate() {
+            let mut visited = String::new();
+            g.visit_one(i as u128, |part| visited.push_str(&part));
+            assert_eq!(&visited, s);
+        }
+
+        expected.sort();
+        generated.sort();
+        assert_eq!(expected, generated);
+    }
+
+    #[test]
+    fn repeated_mn_lengths() {
+        let x24 = Generator::from("x") * (2, 4);
+        let values: Vec<_> = x24.generate_all().collect();
+        assert_eq!(vec!["xx", "xxx", "xxxx"], values);
+
+        let ab23 = oneof!('a', 'b') * (2, 3);
+        let values: Vec<_> = ab23.generate_all().collect();
+        assert_eq!(
+            vec!["aa", "ab", "ba", "bb", "aaa", "aab", "aba", "abb", "baa", "bab", "bba", "bbb"],
+            values
+        );
+    }
+
+    #[test]
+    fn repeated_mn_exhaustive() {
+        assert_matches_expansion(&(Generator::from("x") * (2, 4)));
+        assert_matches_expansion(&(oneof!('a', 'b') * (2, 3)));
+        assert_matches_expansion(&(Generator::Digit * (1, 3)));
+        assert_matches_expansion(&(oneof!("foo", "bar") * (0, 2)));
+        assert_matches_expansion(
+            &(oneof!('a', 'b', 'c') * (1, 2) + '-' + Generator::HexLower * (1, 2)),
+        );
+        assert_matches_expansion(&((oneof!('a', 'b') * (1, 2)) * (2, 3)));
+        assert_matches_expansion(&((oneof!('x', 'y') * (1, 2)).optional() * 2 + Generator::Digit));
+        assert_matches_expansion(
+            &((Generator::Digit * (1, 2))
+                .transform(|s: String| s.trim_start_matches('0').to_string())
+                * (1, 2)),
+        );
+    }
+
     #[test]
     fn oneof_bitorassign_oneof() {
         let mut g = oneof!('a', 'b');
@@ -1180,4 +1726,46 @@ mod tests {
 
         assert_eq!(g.len(), 6);
     }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        use crate::BuiltinTransform;
+        let round_trip = |g: &Generator| {
+            let json = serde_json::to_string(g).unwrap();
+            let h: Generator = serde_json::from_str(&json).unwrap();
+            assert_eq!(&h, g, "{json}");
+            assert_eq!(
+                h.generate_all().collect::<Vec<_>>(),
+                g.generate_all().collect::<Vec<_>>()
+            );
+        };
+        round_trip(
+            &(oneof!("a", "bc").optional() + Generator::class("x-z") * (1, 2) + Generator::Empty),
+        );
+        round_trip(&(Generator::decimal(-1.0, 2.5, 1) | Generator::roman_numerals(1..=20)));
+        round_trip(&Generator::dates("2024-02-27", "2024-03-02", "%d %b %Y"));
+        round_trip(&Generator::times("23:59:50", "23:59:59", "%T"));
+        round_trip(
+            &(Generator::Digit * 2)
+                .transform(BuiltinTransform::TrimLeadingZeros)
+                .transform(TransformFn::from(BuiltinTransform::Suffix("!".into())).with_name("!")),
+        );
+        round_trip(&(Generator::AlphaLower * 2).difference(&oneof!("ab", "zz")));
+
+        // Closures can't be serialized
+        let g = Generator::Digit.transform(|s: String| s.repeat(2));
+        assert!(serde_json::to_string(&g).is_err());
+        let g = Generator::Digit.filter(|s| s != "0");
+        assert!(serde_json::to_string(&g).is_err());
+
+        // Deserialized values are checked
+        let json = r#"{"IntRange":{"start":1,"end":0,"scale":0,"format":{"radix":10,"width":0,"plus_sign":false,"separator":null,"decimal_point":".","uppercase":false,"notation":"Digits"}}}"#;
+        assert!(serde_json::from_str::<Generator>(json).is_err());
+        let json =
+            r#"{"DateTime":{"kind":"Date","start":"2024-02-30","end":"2024-03-01","format":"%F"}}"#;
+        assert!(serde_json::from_str::<Generator>(json).is_err());
+        let json = r#"{"Automaton":[{"accepting":true,"moves":[{"lo":97,"hi":97,"to":0}]}]}"#;
+        assert!(serde_json::from_str::<Generator>(json).is_err());
+    }
 }
@@ -0,0 +1,127 @@
+//! The integer types used to count and index the values of a [`Generator`](crate::Generator).
+//!
+//! Generation, visiting, and cardinality are written once against [`Index`] and used with `u128`
+//! and, with the `bigint` feature, [`BigUint`](num_bigint::BigUint).
+
+/// An unsigned integer suitable for counting and indexing a `Generator`'s values.
+///
+/// The `checked_*` functions return `None` on overflow; types with unbounded precision never do.
+pub(crate) trait Index: Clone + Ord + std::fmt::Debug {
+    /// Whether values of this type are actual indices, rather than placeholders for parsing
+    /// without them.
+    const INDEXED: bool = true;
+
+    fn from_u128(n: u128) -> Self;
+
+    /// Converts to a `u128`, or `None` if the value doesn't fit.
+    fn to_u128(&self) -> Option<u128>;
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+
+    fn checked_pow(&self, exp: usize) -> Option<Self>;
+
+    /// Subtracts `rhs`, which must not exceed `self`.
+    fn sub(&self, rhs: &Self) -> Self;
+
+    /// Returns the quotient and remainder of dividing by `rhs`, which must be nonzero.
+    fn div_rem(&self, rhs: &Self) -> (Self, Self);
+
+    fn zero() -> Self {
+        Self::from_u128(0)
+    }
+
+    /// Converts to a `BigUint`. Only types whose values can exceed `u128::MAX` need to override
+    /// this.
+    #[cfg(feature = "bigint")]
+    fn to_big(&self) -> num_bigint::BigUint {
+        self.to_u128().expect("the value fits in a u128").into()
+    }
+
+    /// Converts from a `BigUint`, or `None` if the value doesn't fit.
+    #[cfg(feature = "bigint")]
+    fn from_big(n: &num_bigint::BigUint) -> Option<Self> {
+        u128::try_from(n).ok().map(Self::from_u128)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+
+    /// Divides `self` by a small `base` in place, returning the remainder.
+    fn div_rem_small(&mut self, base: usize) -> usize {
+        let (q, r) = self.div_rem(&Self::from_u128(base as u128));
+        *self = q;
+        r.to_u128().expect("remainder is less than the base") as usize
+    }
+}
+
+impl Index for u128 {
+    fn from_u128(n: u128) -> Self {
+        n
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        Some(*self)
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        u128::checked_add(*self, *rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        u128::checked_mul(*self, *rhs)
+    }
+
+    fn checked_pow(&self, exp: usize) -> Option<Self> {
+        u128::checked_pow(*self, u32::try_from(exp).ok()?)
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        self - rhs
+    }
+
+    fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        (self / rhs, self % rhs)
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl Index for num_bigint::BigUint {
+    fn from_u128(n: u128) -> Self {
+        n.into()
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        u128::try_from(self).ok()
+    }
+
+    fn to_big(&self) -> num_bigint::BigUint {
+        self.clone()
+    }
+
+    fn from_big(n: &num_bigint::BigUint) -> Option<Self> {
+        Some(n.clone())
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+
+    fn checked_pow(&self, exp: usize) -> Option<Self> {
+        Some(self.pow(u32::try_from(exp).ok()?))
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        self - rhs
+    }
+
+    fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        (self / rhs, self % rhs)
+    }
+}
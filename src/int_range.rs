@@ -0,0 +1,1237 @@
+//! Ranges of integers and fixed-precision decimals, for use as [`Generator::IntRange`].
+use crate::numerals::{self, ordinal_suffix};
+use crate::{CharClass, Generator};
+use std::ops::RangeInclusive;
+
+/// How the integers of an [`IntRange`] are spelled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Notation {
+    /// Digits, such as `112`.
+    #[default]
+    Digits,
+
+    /// Base-10 digits followed by their English ordinal suffix, such as `112th`.
+    Ordinal,
+
+    /// English words, such as `one hundred twelve`, with `minus` before negative values. The
+    /// rest of the format doesn't apply.
+    Words,
+
+    /// Roman numerals from 1 to 3999, such as `CXII`. The rest of the format doesn't apply.
+    Roman,
+
+    /// Normalized base-10 scientific notation, with one digit before the decimal point and no
+    /// trailing zeros after it, such as `1.12e2` for 112.0, `1e2` for 100, and `0e0` for zero.
+    /// The width and separator don't apply.
+    Scientific,
+}
+
+/// How the integers of an [`IntRange`] are written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntFormat {
+    /// The base the digits are written in, from 2 to 36.
+    pub radix: u32,
+
+    /// The minimum number of digits, with leading zeros added to reach it.
+    pub width: usize,
+
+    /// Whether non-negative values have a leading `+`. Negative values always have a `-`.
+    pub plus_sign: bool,
+
+    /// A separator between each group of three digits, counting from the right, such as the `,`
+    /// in `1,234,567`.
+    pub separator: Option<char>,
+
+    /// The decimal point of decimals and scientific notation, such as the `,` of `3,14` in many
+    /// locales.
+    pub decimal_point: char,
+
+    /// Whether digits above 9, and the `E` of scientific notation, are uppercase letters.
+    pub uppercase: bool,
+
+    /// Whether values are written as digits, ordinals, words, Roman numerals, or scientific
+    /// notation.
+    pub notation: Notation,
+}
+
+/// Base-10 numbers with no padding, plus sign, or separators, and `.` as the decimal point.
+impl Default for IntFormat {
+    fn default() -> Self {
+        Self {
+            radix: 10,
+            width: 0,
+            plus_sign: false,
+            separator: None,
+            decimal_point: '.',
+            uppercase: false,
+            notation: Notation::Digits,
+        }
+    }
+}
+
+/// An inclusive range of integers, each written once as an [`IntFormat`] describes, in ascending
+/// order.
+///
+/// Unlike a repetition of [`Digit`](Generator::Digit), a number's digits aren't generated
+/// independently, so there are no duplicates or unwanted leading zeros.
+///
+/// A range with a [`scale`](Self::scale) counts in fractions of a whole number instead, such as
+/// hundredths for prices.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "IntRangeParts")
+)]
+pub struct IntRange {
+    start: i128,
+    end: i128,
+    scale: u32,
+    format: IntFormat,
+}
+
+/// The fields of a deserialized [`IntRange`], before they're checked
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct IntRangeParts {
+    start: i128,
+    end: i128,
+    scale: u32,
+    format: IntFormat,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<IntRangeParts> for IntRange {
+    type Error = String;
+
+    fn try_from(parts: IntRangeParts) -> Result<Self, Self::Error> {
+        Self::try_new(parts.start, parts.end, parts.scale, parts.format)
+    }
+}
+
+impl IntRange {
+    /// Panics if the range is empty or can't be written as `format` describes.
+    fn new(start: i128, end: i128, scale: u32, format: IntFormat) -> Self {
+        match Self::try_new(start, end, scale, format) {
+            Ok(range) => range,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Like [`new`](Self::new), but returns why the range is invalid rather than panicking.
+    fn try_new(start: i128, end: i128, scale: u32, format: IntFormat) -> Result<Self, String> {
+        if start > end {
+            return Err(format!("the range {start}..={end} is empty"));
+        }
+        if !(2..=36).contains(&format.radix) {
+            return Err(format!("the radix {} isn't from 2 to 36", format.radix));
+        }
+        let mistaken = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '+';
+        if let Some(c) = format.separator {
+            if c.is_digit(format.radix) || mistaken(c) {
+                return Err(format!(
+                    "the separator {c:?} could be mistaken for a digit or sign"
+                ));
+            }
+        }
+
+        let decimal = scale > 0 || format.notation == Notation::Scientific;
+        if decimal {
+            let c = format.decimal_point;
+            if mistaken(c) || Some(c) == format.separator {
+                return Err(format!(
+                    "the decimal point {c:?} could be mistaken for a digit, sign, or separator"
+                ));
+            }
+            if format.radix != 10 {
+                return Err("decimals must be base 10".into());
+            }
+            if 10u128.checked_pow(scale).is_none() {
+                return Err(format!("the scale {scale} is more than 38 digits"));
+            }
+        }
+        match format.notation {
+            Notation::Ordinal if format.radix != 10 => {
+                return Err("ordinals must be base 10".into());
+            }
+            Notation::Roman if start < 1 || end > numerals::ROMAN_MAX as i128 => {
+                return Err(format!(
+                    "the range {start}..={end} isn't all Roman numerals from 1 to 3999"
+                ));
+            }
+            _ => {}
+        }
+        if scale > 0 && !matches!(format.notation, Notation::Digits | Notation::Scientific) {
+            return Err("decimals can only be written as digits or in scientific notation".into());
+        }
+
+        Ok(Self {
+            start,
+            end,
+            scale,
+            format,
+        })
+    }
+
+    /// The smallest value, in units of the scale.
+    pub fn start(&self) -> i128 {
+        self.start
+    }
+
+    /// The largest value, in units of the scale.
+    pub fn end(&self) -> i128 {
+        self.end
+    }
+
+    /// The number of digits after the decimal point, so that each value counts units of
+    /// `10^-scale`.
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// How values are written.
+    pub fn format(&self) -> &IntFormat {
+        &self.format
+    }
+
+    /// The number of values less one, which always fits in a `u128`.
+    pub(crate) fn span(&self) -> u128 {
+        (self.end as u128).wrapping_sub(self.start as u128)
+    }
+
+    /// The value at `offset` from the start, which must not exceed [`span`](Self::span).
+    pub(crate) fn get(&self, offset: u128) -> i128 {
+        (self.start as u128).wrapping_add(offset) as i128
+    }
+
+    /// Appends `n` to `out` as this range's format describes.
+    pub(crate) fn write(&self, n: i128, out: &mut String) {
+        let magnitude = n.unsigned_abs();
+        match self.format.notation {
+            Notation::Words => {
+                if n < 0 {
+                    out.push_str("minus ");
+                }
+                numerals::write_words(magnitude, out);
+            }
+            Notation::Roman => numerals::write_roman(magnitude, out),
+            Notation::Digits | Notation::Ordinal | Notation::Scientific => {
+                if n < 0 {
+                    out.push('-');
+                } else if self.format.plus_sign {
+                    out.push('+');
+                }
+                match self.format.notation {
+                    Notation::Ordinal => {
+                        self.write_digits(magnitude, out);
+                        out.push_str(ordinal_suffix(magnitude));
+                    }
+                    Notation::Scientific => self.write_scientific(magnitude, out),
+                    _ => self.write_digits(magnitude, out),
+                }
+            }
+        }
+    }
+
+    /// Appends the digits of `n`, with any padding, separators, and decimal point.
+    fn write_digits(&self, n: u128, out: &mut String) {
+        self.push_digits(&self.digits(n, self.min_digits()), out);
+    }
+
+    /// Appends the mantissa and exponent of `n` in scientific notation.
+    fn write_scientific(&self, n: u128, out: &mut String) {
+        let mut digits = self.digits(n, 0);
+        let exponent = match n {
+            0 => 0,
+            _ => digits.len() as i64 - 1 - i64::from(self.scale),
+        };
+        while digits.len() > 1 && digits.last() == Some(&0) {
+            digits.pop();
+        }
+        self.push_digits(&digits, out);
+        out.push_str(&self.exponent(exponent));
+    }
+
+    /// Appends `digits`, with the separators or decimal point that follow them.
+    fn push_digits(&self, digits: &[u32], out: &mut String) {
+        for (i, d) in digits.iter().enumerate() {
+            out.push(self.digit_char(*d));
+            if let Some(mark) = self.mark_after(i, digits.len()) {
+                out.push(mark);
+            }
+        }
+    }
+
+    /// The fewest digits a value is written with, including padding and those after the decimal
+    /// point.
+    fn min_digits(&self) -> usize {
+        match self.format.notation {
+            Notation::Scientific => 0,
+            _ if self.scale > 0 => self.format.width.max(1) + self.scale as usize,
+            _ => self.format.width,
+        }
+    }
+
+    /// The exponent of scientific notation, with its `e`.
+    fn exponent(&self, exponent: i64) -> String {
+        let e = if self.format.uppercase { 'E' } else { 'e' };
+        format!("{e}{exponent}")
+    }
+
+    /// The digits of `n`, most significant first, with leading zeros to make at least `len` of
+    /// them.
+    fn digits(&self, mut n: u128, len: usize) -> Vec<u32> {
+        let radix = u128::from(self.format.radix);
+        let mut digits = vec![];
+        while n > 0 || digits.is_empty() {
+            digits.push((n % radix) as u32);
+            n /= radix;
+        }
+        if digits.len() < len {
+            digits.resize(len, 0);
+        }
+        digits.reverse();
+        digits
+    }
+
+    fn digit_char(&self, d: u32) -> char {
+        let c = char::from_digit(d, self.format.radix).expect("digits are less than the radix");
+        if self.format.uppercase {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        }
+    }
+
+    /// The separator or decimal point that follows digit `i` of a `len`-digit number, if any.
+    fn mark_after(&self, i: usize, len: usize) -> Option<char> {
+        if self.format.notation == Notation::Scientific {
+            return (i == 0 && len > 1).then_some(self.format.decimal_point);
+        }
+        let whole = len - self.scale as usize;
+        if self.scale > 0 && i + 1 == whole {
+            return Some(self.format.decimal_point);
+        }
+        let separator = self.format.separator?;
+        (i + 1 < whole && (whole - 1 - i).is_multiple_of(3)).then_some(separator)
+    }
+
+    /// Finds every prefix of `s` that is a value of this range, returning the length of each and
+    /// its offset from the start.
+    pub(crate) fn parse_prefixes(&self, s: &str) -> Vec<(usize, u128)> {
+        let longest = match self.format.notation {
+            Notation::Words => usize::MAX,
+            Notation::Roman => numerals::ROMAN_LONGEST,
+            // A sign, every digit, the decimal point, and an exponent of at most -38
+            Notation::Scientific => {
+                let digits = self.digits(self.start.unsigned_abs().max(self.end.unsigned_abs()), 0);
+                digits.len() + self.format.decimal_point.len_utf8() + 5
+            }
+            Notation::Digits | Notation::Ordinal => [self.start, self.end]
+                .iter()
+                .map(|n| {
+                    let mut s = String::new();
+                    self.write(*n, &mut s);
+                    s.len()
+                })
+                .max()
+                .expect("there are two bounds"),
+        };
+
+        // The characters a value can have, though not every combination of them is a value
+        let allowed = |i: usize, c: char| match self.format.notation {
+            Notation::Words => c.is_ascii_lowercase() || c == ' ' || c == '-',
+            Notation::Roman => "IVXLCDM".contains(c),
+            Notation::Digits | Notation::Ordinal | Notation::Scientific => {
+                (i == 0 && (c == '-' || c == '+'))
+                    || Some(c) == self.format.separator
+                    || c == self.format.decimal_point
+                    || c.to_digit(self.format.radix)
+                        .is_some_and(|d| self.digit_char(d) == c)
+                    || match self.format.notation {
+                        Notation::Ordinal => matches!(c, 's' | 't' | 'n' | 'd' | 'r' | 'h'),
+                        Notation::Scientific => matches!(c, 'e' | 'E' | '-'),
+                        _ => false,
+                    }
+            }
+        };
+        let candidate_ends = s
+            .char_indices()
+            .take_while(|(i, c)| *i < longest && allowed(*i, *c))
+            .map(|(i, c)| i + c.len_utf8());
+
+        let mut matches = vec![];
+        let mut written = String::new();
+        for end in candidate_ends {
+            let candidate = &s[..end];
+            let Some(n) = self.parse_value(candidate) else {
+                continue;
+            };
+            if n < self.start || n > self.end {
+                continue;
+            }
+            // Only the canonical spelling of a value is produced
+            written.clear();
+            self.write(n, &mut written);
+            if written == candidate {
+                matches.push((end, (n as u128).wrapping_sub(self.start as u128)));
+            }
+        }
+        matches
+    }
+
+    /// Reads the value of `s`, ignoring where its separators and decimal point are, or what an
+    /// ordinal's suffix is.
+    fn parse_value(&self, s: &str) -> Option<i128> {
+        let (negative, magnitude) = match self.format.notation {
+            Notation::Words => match s.strip_prefix("minus ") {
+                Some(words) => (true, numerals::parse_words(words)?),
+                None => (false, numerals::parse_words(s)?),
+            },
+            Notation::Roman => (false, numerals::parse_roman(s)?),
+            Notation::Digits | Notation::Ordinal | Notation::Scientific => {
+                let (s, exponent) = match self.format.notation {
+                    Notation::Ordinal => (s.get(..s.len().checked_sub(2)?)?, None),
+                    Notation::Scientific => {
+                        let (mantissa, exponent) = s.split_once(['e', 'E'])?;
+                        (mantissa, Some(exponent.parse::<i64>().ok()?))
+                    }
+                    _ => (s, None),
+                };
+                let (negative, digits) = match s.strip_prefix('-') {
+                    Some(digits) => (true, digits),
+                    None => (false, s.strip_prefix('+').unwrap_or(s)),
+                };
+                let digits: String = digits
+                    .chars()
+                    .filter(|c| {
+                        Some(*c) != self.format.separator && *c != self.format.decimal_point
+                    })
+                    .collect();
+                let n = u128::from_str_radix(&digits, self.format.radix).ok()?;
+                match exponent {
+                    // The mantissa's digits are in units of `10^(exponent - digits + 1)`
+                    Some(exponent) => {
+                        let shift = exponent + i64::from(self.scale) + 1 - digits.len() as i64;
+                        let unit = 10u128.checked_pow(u32::try_from(shift.unsigned_abs()).ok()?)?;
+                        let n = if shift >= 0 {
+                            n.checked_mul(unit)?
+                        } else if n % unit == 0 {
+                            n / unit
+                        } else {
+                            return None;
+                        };
+                        (negative, n)
+                    }
+                    None => (negative, n),
+                }
+            }
+        };
+        if negative {
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    /// A generator of the same values built from characters, classes, and repetitions, though
+    /// in a different order, so that they can be rendered as a regex.
+    pub(crate) fn to_generator(&self) -> Generator {
+        let (minus, plus) = match self.format.notation {
+            Notation::Words => (Generator::from("minus "), Generator::Empty),
+            _ if self.format.plus_sign => (Generator::Char('-'), Generator::Char('+')),
+            _ => (Generator::Char('-'), Generator::Empty),
+        };
+
+        let mut branches = vec![];
+        if self.start < 0 {
+            let smallest = if self.end < 0 {
+                self.end.unsigned_abs()
+            } else {
+                1
+            };
+            let magnitudes = self.unsigned(smallest, self.start.unsigned_abs());
+            branches.push(sequence(vec![minus, magnitudes]));
+        }
+        if self.end >= 0 {
+            let magnitudes = self.unsigned(self.start.max(0) as u128, self.end as u128);
+            branches.push(sequence(vec![plus, magnitudes]));
+        }
+        one_of(branches)
+    }
+
+    /// Matches each number from `lo` to `hi` without its sign.
+    fn unsigned(&self, lo: u128, hi: u128) -> Generator {
+        match self.format.notation {
+            Notation::Digits => self.magnitudes(lo, hi, 0),
+            Notation::Scientific => self.scientific(lo, hi),
+            Notation::Ordinal => self.ordinals(lo, hi),
+            Notation::Words => numerals::words(lo, hi),
+            Notation::Roman => numerals::roman(lo, hi),
+        }
+    }
+
+    /// Matches the digits and suffix of each number from `lo` to `hi`, splitting off the last two
+    /// digits, which decide the suffix.
+    fn ordinals(&self, lo: u128, hi: u128) -> Generator {
+        let mut branches = vec![];
+        if lo < 100 {
+            // Numbers below 100 have no hundreds digit, though they may be padded to the width
+            let hi = hi.min(99);
+            let mut endings = vec![];
+            let mut from = lo;
+            if self.format.width < 2 && lo < 10 {
+                endings.push(ordinal_endings(None, lo..=hi.min(9)));
+                from = 10;
+            }
+            if from <= hi {
+                for (tens, ones) in numerals::split(from, hi, 10) {
+                    endings.push(ordinal_endings(Some(tens), ones));
+                }
+            }
+            let zeros = vec![0; self.format.width.saturating_sub(2)];
+            branches.push(sequence(vec![
+                self.between(&zeros, &zeros, 0, 2),
+                one_of(endings),
+            ]));
+        }
+        if hi >= 100 {
+            for (hundreds, rest) in numerals::split(lo.max(100), hi, 100) {
+                let (lo, hi) = rest.into_inner();
+                let endings = numerals::split(lo, hi, 10)
+                    .into_iter()
+                    .map(|(tens, ones)| ordinal_endings(Some(tens), ones))
+                    .collect();
+                let (lo, hi) = hundreds.into_inner();
+                branches.push(sequence(vec![self.magnitudes(lo, hi, 2), one_of(endings)]));
+            }
+        }
+        one_of(branches)
+    }
+
+    /// Matches the digits of each number from `lo` to `hi`, grouped by how many digits they have,
+    /// when `trailing` more digits follow them.
+    fn magnitudes(&self, lo: u128, hi: u128, trailing: usize) -> Generator {
+        let width = self.min_digits().saturating_sub(trailing);
+        let (min_len, max_len) = (self.digits(lo, width).len(), self.digits(hi, width).len());
+        let radix = u128::from(self.format.radix);
+
+        let branches = (min_len..=max_len)
+            .map(|len| {
+                // The numbers written with exactly `len` digits, including any padding
+                let smallest = if len == min_len {
+                    lo
+                } else {
+                    radix.pow(len as u32 - 1)
+                };
+                let largest = match radix.checked_pow(len as u32) {
+                    Some(limit) if len < max_len => limit - 1,
+                    _ => hi,
+                };
+                let (lo, hi) = (self.digits(smallest, len), self.digits(largest, len));
+                self.between(&lo, &hi, 0, trailing)
+            })
+            .collect();
+        one_of(branches)
+    }
+
+    /// Matches each number from `lo` to `hi` in scientific notation, grouped by how many digits
+    /// they have, which decides the exponent, and then by how many of them are trailing zeros.
+    fn scientific(&self, lo: u128, hi: u128) -> Generator {
+        let mut branches = vec![];
+        if lo == 0 {
+            branches.push(Generator::from(format!("0{}", self.exponent(0))));
+        }
+        let lo = lo.max(1);
+        if lo > hi {
+            return one_of(branches);
+        }
+
+        let (min_len, max_len) = (self.digits(lo, 0).len(), self.digits(hi, 0).len());
+        for len in min_len..=max_len {
+            let smallest = if len == min_len {
+                lo
+            } else {
+                10u128.pow(len as u32 - 1)
+            };
+            let largest = match 10u128.checked_pow(len as u32) {
+                Some(limit) if len < max_len => limit - 1,
+                _ => hi,
+            };
+
+            let mut mantissas = vec![];
+            for zeros in 0..len {
+                // The numbers ending in exactly `zeros` zeros, whose other digits are written
+                let unit = 10u128.pow(zeros as u32);
+                let (lo, hi) = (smallest.div_ceil(unit), largest / unit);
+                let written = len - zeros;
+                if lo > hi {
+                    continue;
+                }
+                if written == 1 {
+                    mantissas.push(self.digit_class(lo as u32, hi as u32));
+                    continue;
+                }
+
+                // The last digit written isn't a zero
+                let ending = |from: u128, to: u128, first: u128, last: u128| {
+                    (from <= to && first.max(1) <= last).then(|| {
+                        let (from, to) = (self.digits(from, 0), self.digits(to, 0));
+                        sequence(vec![
+                            self.between(&from, &to, 0, 1),
+                            self.digit_class(first.max(1) as u32, last as u32),
+                        ])
+                    })
+                };
+                let (lo_rest, lo_last, hi_rest, hi_last) = (lo / 10, lo % 10, hi / 10, hi % 10);
+                if lo_rest == hi_rest {
+                    mantissas.extend(ending(lo_rest, lo_rest, lo_last, hi_last));
+                } else {
+                    mantissas.extend(ending(lo_rest, lo_rest, lo_last, 9));
+                    mantissas.extend(ending(lo_rest + 1, hi_rest - 1, 1, 9));
+                    mantissas.extend(ending(hi_rest, hi_rest, 1, hi_last));
+                }
+            }
+            let exponent = len as i64 - 1 - i64::from(self.scale);
+            branches.push(sequence(vec![
+                one_of(mantissas),
+                self.exponent(exponent).into(),
+            ]));
+        }
+        one_of(branches)
+    }
+
+    /// Matches the digits from `lo` to `hi`, which have the same length, as the digits starting
+    /// at position `i` of a number with `trailing` more digits after them.
+    fn between(&self, lo: &[u32], hi: &[u32], i: usize, trailing: usize) -> Generator {
+        let (Some((first_lo, rest_lo)), Some((first_hi, rest_hi))) =
+            (lo.split_first(), hi.split_first())
+        else {
+            return Generator::Empty;
+        };
+        let end = i + lo.len();
+        let len = end + trailing;
+        let max = self.format.radix - 1;
+        if lo.iter().all(|d| *d == 0) && hi.iter().all(|d| *d == max) {
+            return self.any_digits(i, end, len);
+        }
+
+        if first_lo == first_hi {
+            let rest = self.between(rest_lo, rest_hi, i + 1, trailing);
+            return sequence(vec![
+                self.digit_class(*first_lo, *first_hi),
+                self.separator(i, len),
+                rest,
+            ]);
+        }
+
+        // Split off the first and last leading digits unless every number after them is included
+        let from_zero = rest_lo.iter().all(|d| *d == 0);
+        let to_max = rest_hi.iter().all(|d| *d == max);
+        let mut branches = vec![];
+        if !from_zero {
+            let rest = self.between(rest_lo, &vec![max; rest_hi.len()], i + 1, trailing);
+            branches.push(sequence(vec![
+                self.digit_class(*first_lo, *first_lo),
+                self.separator(i, len),
+                rest,
+            ]));
+        }
+        let middle_lo = if from_zero { *first_lo } else { first_lo + 1 };
+        let middle_hi = if to_max { *first_hi } else { first_hi - 1 };
+        if middle_lo <= middle_hi {
+            branches.push(sequence(vec![
+                self.digit_class(middle_lo, middle_hi),
+                self.separator(i, len),
+                self.any_digits(i + 1, end, len),
+            ]));
+        }
+        if !to_max {
+            let rest = self.between(&vec![0; rest_lo.len()], rest_hi, i + 1, trailing);
+            branches.push(sequence(vec![
+                self.digit_class(*first_hi, *first_hi),
+                self.separator(i, len),
+                rest,
+            ]));
+        }
+        one_of(branches)
+    }
+
+    /// Matches any digits from position `i` up to `end` of a `len`-digit number.
+    fn any_digits(&self, i: usize, end: usize, len: usize) -> Generator {
+        let mut parts = vec![];
+        let mut run = 0;
+        for j in i..end {
+            run += 1;
+            let separator = self.separator(j, len);
+            if separator != Generator::Empty || j + 1 == end {
+                let any = self.digit_class(0, self.format.radix - 1);
+                parts.push(if run == 1 { any } else { any * run });
+                parts.push(separator);
+                run = 0;
+            }
+        }
+        sequence(parts)
+    }
+
+    /// Matches the separator or decimal point after digit `i` of a `len`-digit number.
+    fn separator(&self, i: usize, len: usize) -> Generator {
+        match self.mark_after(i, len) {
+            Some(c) => Generator::Char(c),
+            None => Generator::Empty,
+        }
+    }
+
+    /// Matches the digits from `lo` to `hi`, preferring the built-in classes.
+    fn digit_class(&self, lo: u32, hi: u32) -> Generator {
+        if lo == hi {
+            return Generator::Char(self.digit_char(lo));
+        }
+        let max = self.format.radix - 1;
+        match (self.format.radix, self.format.uppercase) {
+            (10, _) if (lo, hi) == (0, max) => Generator::Digit,
+            (16, true) if (lo, hi) == (0, max) => Generator::HexUpper,
+            (16, false) if (lo, hi) == (0, max) => Generator::HexLower,
+            _ => {
+                let class: CharClass = (lo..=hi).map(|d| self.digit_char(d)).collect();
+                Generator::CharClass(class)
+            }
+        }
+    }
+}
+
+/// The number of `10^-scale` units in `x`, rounded up or down to a whole number of them.
+fn scaled(x: f64, scale: u32, round_up: bool) -> i128 {
+    assert!(x.is_finite(), "{x} isn't finite");
+
+    // The shortest decimal that converts back to `x`, which is never in scientific notation
+    let written = x.abs().to_string();
+    let (whole, fraction) = written.split_once('.').unwrap_or((&written, ""));
+    let (kept, dropped) = fraction.split_at(fraction.len().min(scale as usize));
+    let digits = format!("{whole}{kept:0<width$}", width = scale as usize);
+    let too_large = || panic!("{x} is too large to count in units of 10^-{scale}");
+    let magnitude: i128 = digits.parse().unwrap_or_else(|_| too_large());
+
+    // Dropping digits rounded toward zero
+    let inexact = dropped.bytes().any(|b| b != b'0');
+    let n = if x < 0.0 { -magnitude } else { magnitude };
+    match (inexact, round_up, x < 0.0) {
+        (true, true, false) => n.checked_add(1).unwrap_or_else(too_large),
+        (true, false, true) => n.checked_sub(1).unwrap_or_else(too_large),
+        _ => n,
+    }
+}
+
+/// Matches the last two digits of ordinals, one of the `tens` (or none, for a single digit)
+/// followed by one of the `ones`, and then their suffix.
+fn ordinal_endings(tens: Option<RangeInclusive<u128>>, ones: RangeInclusive<u128>) -> Generator {
+    let mut branches = vec![];
+    let mut other_tens = Generator::Empty;
+    if let Some(tens) = tens {
+        if tens.contains(&1) {
+            let teens = decimal_class(ones.clone()).expect("there are ones digits");
+            branches.push(sequence(vec![
+                Generator::Char('1'),
+                teens,
+                Generator::from("th"),
+            ]));
+        }
+        match decimal_class(tens.filter(|t| *t != 1)) {
+            Some(class) => other_tens = class,
+            None => return one_of(branches),
+        }
+    }
+    for suffix in ["st", "nd", "rd", "th"] {
+        if let Some(ones) = decimal_class(ones.clone().filter(|d| ordinal_suffix(*d) == suffix)) {
+            branches.push(sequence(vec![
+                other_tens.clone(),
+                ones,
+                Generator::from(suffix),
+            ]));
+        }
+    }
+    one_of(branches)
+}
+
+/// Matches any of the decimal `digits`, if there are any.
+fn decimal_class(digits: impl Iterator<Item = u128>) -> Option<Generator> {
+    let chars: Vec<_> = digits
+        .map(|d| char::from_digit(d as u32, 10).expect("digits are less than 10"))
+        .collect();
+    match chars.len() {
+        0 => None,
+        1 => Some(Generator::Char(chars[0])),
+        10 => Some(Generator::Digit),
+        _ => Some(Generator::CharClass(chars.into_iter().collect())),
+    }
+}
+
+/// A `Sequence` of the non-empty `parts`, or the part itself if there's only one.
+pub(crate) fn sequence(mut parts: Vec<Generator>) -> Generator {
+    parts.retain(|p| *p != Generator::Empty);
+    match parts.len() {
+        0 => Generator::Empty,
+        1 => parts.remove(0),
+        _ => Generator::Sequence(parts),
+    }
+}
+
+/// A `OneOf` the `branches`, or the branch itself if there's only one.
+pub(crate) fn one_of(mut branches: Vec<Generator>) -> Generator {
+    match branches.len() {
+        1 => branches.remove(0),
+        _ => Generator::OneOf {
+            v: branches,
+            is_optional: false,
+        },
+    }
+}
+
+impl Generator {
+    /// Generates each integer in `range` exactly once, in ascending order and without leading
+    /// zeros.
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// let g = Generator::int_range(-5..=120);
+    /// assert_eq!(g.len(), 126);
+    /// assert_eq!(g.generate_one(0), "-5");
+    /// assert_eq!(g.generate_one(12), "7");
+    /// assert_eq!(g.generate_one(125), "120");
+    /// assert_eq!(g.regex(), r"-[1-5]|\d|[1-9]\d|1([01]\d|20)");
+    /// ```
+    pub fn int_range(range: RangeInclusive<i128>) -> Self {
+        Self::int_range_with(range, IntFormat::default())
+    }
+
+    /// Generates each integer in `range` exactly once, in ascending order, written as `format`
+    /// describes.
+    ///
+    /// Panics if the range is empty, the radix isn't from 2 to 36, or the separator could be
+    /// mistaken for a digit or sign. Ordinals must be base 10, and Roman numerals must be from 1
+    /// to 3999.
+    ///
+    /// ```
+    /// use generator_combinator::{Generator, IntFormat};
+    /// let format = IntFormat {
+    ///     width: 4,
+    ///     separator: Some(','),
+    ///     ..Default::default()
+    /// };
+    /// let g = Generator::int_range_with(0..=20_000, format);
+    /// assert_eq!(g.generate_one(42), "0,042");
+    /// assert_eq!(g.generate_one(12_345), "12,345");
+    ///
+    /// let format = IntFormat {
+    ///     radix: 16,
+    ///     width: 2,
+    ///     uppercase: true,
+    ///     ..Default::default()
+    /// };
+    /// let g = Generator::int_range_with(0..=255, format);
+    /// assert_eq!(g.generate_one(171), "AB");
+    /// assert_eq!(g.regex(), r"[\dA-F]{2}");
+    /// ```
+    pub fn int_range_with(range: RangeInclusive<i128>, format: IntFormat) -> Self {
+        let (start, end) = range.into_inner();
+        Generator::IntRange(IntRange::new(start, end, 0, format))
+    }
+
+    /// Generates each decimal from `min` to `max` with `scale` digits after the decimal point
+    /// exactly once, in ascending order.
+    ///
+    /// `min` is rounded up and `max` rounded down to the nearest decimal at that scale, so that
+    /// `0.1` is exactly one tenth. Panics if either isn't finite, there are no decimals between
+    /// them, or they're too large to count in units of `10^-scale`.
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// let g = Generator::decimal(-1.0, 99.99, 2);
+    /// assert_eq!(g.len(), 10_100);
+    /// assert_eq!(g.generate_one(0), "-1.00");
+    /// assert_eq!(g.generate_one(105), "0.05");
+    /// assert_eq!(g.index_of("12.50"), Some(1350));
+    /// assert_eq!(g.index_of("12.5"), None);
+    /// assert_eq!(g.regex(), r"-(0\.(0[1-9]|[1-9]\d)|1\.00)|\d\.\d{2}|[1-9]\d\.\d{2}");
+    /// ```
+    pub fn decimal(min: f64, max: f64, scale: u32) -> Self {
+        Self::decimal_with(min, max, scale, IntFormat::default())
+    }
+
+    /// Generates each decimal from `min` to `max` with `scale` digits after the decimal point
+    /// exactly once, in ascending order, written as `format` describes.
+    ///
+    /// This rounds `min` and `max` and panics as [`decimal`](Self::decimal) does, and also if
+    /// the format isn't base 10 digits or scientific notation, or the decimal point could be
+    /// mistaken for a digit, sign, or separator.
+    ///
+    /// ```
+    /// use generator_combinator::{Generator, IntFormat, Notation};
+    /// let format = IntFormat {
+    ///     separator: Some('.'),
+    ///     decimal_point: ',',
+    ///     ..Default::default()
+    /// };
+    /// let g = Generator::decimal_with(0.0, 10_000.0, 2, format);
+    /// assert_eq!(g.generate_one(123_456), "1.234,56");
+    ///
+    /// let format = IntFormat {
+    ///     notation: Notation::Scientific,
+    ///     ..Default::default()
+    /// };
+    /// let g = Generator::decimal_with(0.0, 1000.0, 1, format);
+    /// assert_eq!(g.generate_one(0), "0e0");
+    /// assert_eq!(g.generate_one(5), "5e-1");
+    /// assert_eq!(g.generate_one(1000), "1e2");
+    /// assert_eq!(g.generate_one(1125), "1.125e2");
+    /// ```
+    pub fn decimal_with(min: f64, max: f64, scale: u32, format: IntFormat) -> Self {
+        let (start, end) = (scaled(min, scale, true), scaled(max, scale, false));
+        Generator::IntRange(IntRange::new(start, end, scale, format))
+    }
+
+    /// Generates each integer in `range` as an English ordinal, in ascending order.
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// let g = Generator::ordinals(1..=112);
+    /// assert_eq!(g.generate_one(0), "1st");
+    /// assert_eq!(g.generate_one(10), "11th");
+    /// assert_eq!(g.generate_one(21), "22nd");
+    /// assert_eq!(g.generate_one(111), "112th");
+    /// ```
+    pub fn ordinals(range: RangeInclusive<i128>) -> Self {
+        let format = IntFormat {
+            notation: Notation::Ordinal,
+            ..Default::default()
+        };
+        Self::int_range_with(range, format)
+    }
+
+    /// Generates each integer in `range` spelled out in English words, in ascending order.
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// let g = Generator::number_words(-1..=1000);
+    /// assert_eq!(g.generate_one(0), "minus one");
+    /// assert_eq!(g.generate_one(22), "twenty-one");
+    /// assert_eq!(g.generate_one(113), "one hundred twelve");
+    /// assert_eq!(g.index_of("one thousand"), Some(1001));
+    /// ```
+    pub fn number_words(range: RangeInclusive<i128>) -> Self {
+        let format = IntFormat {
+            notation: Notation::Words,
+            ..Default::default()
+        };
+        Self::int_range_with(range, format)
+    }
+
+    /// Generates each integer in `range` as a Roman numeral, in ascending order.
+    ///
+    /// Panics if the range isn't within 1 to 3999.
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// let g = Generator::roman_numerals(1..=3999);
+    /// assert_eq!(g.len(), 3999);
+    /// assert_eq!(g.generate_one(3), "IV");
+    /// assert_eq!(g.generate_one(1993), "MCMXCIV");
+    /// assert!(!g.matches("IIII"));
+    /// ```
+    pub fn roman_numerals(range: RangeInclusive<i128>) -> Self {
+        let format = IntFormat {
+            notation: Notation::Roman,
+            ..Default::default()
+        };
+        Self::int_range_with(range, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use std::collections::BTreeSet;
+
+    /// Checks every value of `g` against `expected`, that each maps back to its index, and that
+    /// the regex matches exactly these values.
+    fn assert_values(g: &Generator, expected: impl IntoIterator<Item = String>) {
+        let values: Vec<_> = g.generate_all().collect();
+        assert_eq!(values, expected.into_iter().collect::<Vec<_>>());
+        for (i, s) in values.iter().enumerate() {
+            assert_eq!(g.indices_of(s), [i as u128]);
+        }
+
+        let Generator::IntRange(range) = g else {
+            unreachable!()
+        };
+        let structural: BTreeSet<_> = range.to_generator().generate_all().collect();
+        assert_eq!(structural, values.iter().cloned().collect());
+        assert_eq!(structural.len(), values.len());
+
+        let re = Regex::new(&format!("^(?:{})$", g.try_regex().unwrap())).unwrap();
+        assert!(values.iter().all(|s| re.is_match(s)));
+    }
+
+    #[test]
+    fn decimal() {
+        assert_values(&Generator::int_range(0..=9), (0..=9).map(|n| n.to_string()));
+        assert_values(
+            &Generator::int_range(7..=1234),
+            (7..=1234).map(|n| n.to_string()),
+        );
+        assert_values(
+            &Generator::int_range(-321..=-17),
+            (-321..=-17).map(|n| n.to_string()),
+        );
+        assert_values(
+            &Generator::int_range(-99..=100),
+            (-99..=100).map(|n| n.to_string()),
+        );
+
+        let g = Generator::int_range(100..=999);
+        assert_eq!(g.regex(), r"[1-9]\d{2}");
+        assert!(!g.matches("099"));
+        assert_eq!(Generator::int_range(5..=5).regex(), "5");
+    }
+
+    #[test]
+    fn formats() {
+        let format = IntFormat {
+            width: 3,
+            plus_sign: true,
+            ..Default::default()
+        };
+        assert_values(
+            &Generator::int_range_with(-12..=1010, format),
+            (-12..=1010).map(|n: i32| {
+                if n < 0 {
+                    format!("-{:03}", -n)
+                } else {
+                    format!("+{n:03}")
+                }
+            }),
+        );
+
+        let format = IntFormat {
+            separator: Some('_'),
+            ..Default::default()
+        };
+        let g = Generator::int_range_with(990..=1_000_100, format);
+        assert_eq!(g.generate_one(0), "990");
+        assert_eq!(g.generate_one(10), "1_000");
+        assert_eq!(g.generate_one(1_000_100 - 990), "1_000_100");
+        assert_eq!(g.index_of("12_345"), Some(12_345 - 990));
+        assert_eq!(g.index_of("12345"), None);
+        assert_eq!(g.index_of("1_2345"), None);
+        let re = Regex::new(&format!("^(?:{})$", g.regex())).unwrap();
+        for i in (0..g.len()).step_by(997) {
+            assert!(re.is_match(&g.generate_one(i)));
+        }
+        assert!(!re.is_match("12345"));
+        assert!(!re.is_match("1_000_101"));
+
+        let format = IntFormat {
+            radix: 16,
+            ..Default::default()
+        };
+        assert_values(
+            &Generator::int_range_with(-300..=300, format),
+            (-300..=300i32).map(|n| {
+                if n < 0 {
+                    format!("-{:x}", -n)
+                } else {
+                    format!("{n:x}")
+                }
+            }),
+        );
+
+        let format = IntFormat {
+            radix: 2,
+            width: 4,
+            ..Default::default()
+        };
+        assert_values(
+            &Generator::int_range_with(3..=40, format),
+            (3..=40).map(|n| format!("{n:04b}")),
+        );
+    }
+
+    #[test]
+    fn extremes() {
+        let g = Generator::int_range(i128::MIN..=i128::MAX);
+        assert!(g.try_len().is_err());
+
+        let g = Generator::int_range(i128::MIN..=i128::MIN + 2);
+        assert_eq!(g.generate_one(0), i128::MIN.to_string());
+        assert_eq!(g.index_of(&(i128::MIN + 1).to_string()), Some(1));
+
+        let g = Generator::int_range(0..=i128::MAX);
+        assert_eq!(g.len(), i128::MAX as u128 + 1);
+        assert_eq!(g.generate_one(i128::MAX as u128), i128::MAX.to_string());
+        assert_eq!(g.index_of(&i128::MAX.to_string()), Some(i128::MAX as u128));
+        let re = Regex::new(&format!("^(?:{})$", g.regex())).unwrap();
+        assert!(re.is_match(&i128::MAX.to_string()));
+        assert!(!re.is_match(&(i128::MAX as u128 + 1).to_string()));
+    }
+
+    #[test]
+    fn in_sequence() {
+        // A number followed by digits can split in more than one place
+        let g = Generator::int_range(1..=20) + Generator::Digit;
+        assert_eq!(g.len(), 200);
+        assert_eq!(g.indices_of("123"), [3 * 20 + 11]);
+        assert_eq!(g.indices_of("12"), [2 * 20]);
+        assert!(!g.matches("012"));
+
+        let mut parts = vec![];
+        g.visit_one(3 * 20 + 11, |part| parts.push(part));
+        assert_eq!(parts, ["12", "3"]);
+    }
+
+    #[test]
+    fn notations() {
+        fn ordinal(n: i32) -> String {
+            let suffix = match (n.abs() % 10, n.abs() % 100) {
+                (_, 11..=13) => "th",
+                (1, _) => "st",
+                (2, _) => "nd",
+                (3, _) => "rd",
+                _ => "th",
+            };
+            format!("{n}{suffix}")
+        }
+        assert_values(&Generator::ordinals(-25..=1234), (-25..=1234).map(ordinal));
+        assert_values(&Generator::ordinals(57..=60), (57..=60).map(ordinal));
+        assert_values(&Generator::ordinals(111..=113), (111..=113).map(ordinal));
+        let format = IntFormat {
+            width: 5,
+            separator: Some(','),
+            notation: Notation::Ordinal,
+            ..Default::default()
+        };
+        let g = Generator::int_range_with(0..=123_456, format);
+        assert_eq!(g.generate_one(1), "00,001st");
+        assert_eq!(g.generate_one(123_412), "123,412th");
+        assert_eq!(g.index_of("12,345th"), Some(12_345));
+        assert_eq!(g.index_of("12,345st"), None);
+        let re = Regex::new(&format!("^(?:{})$", g.regex())).unwrap();
+        for i in (0..g.len()).step_by(331) {
+            assert!(re.is_match(&g.generate_one(i)));
+        }
+        assert!(!re.is_match("00,011st"));
+        assert!(!re.is_match("123,457th"));
+
+        let g = Generator::number_words(-120..=2_000_500);
+        assert_eq!(g.len(), 2_000_621);
+        assert_eq!(g.generate_one(0), "minus one hundred twenty");
+        assert_eq!(g.generate_one(120), "zero");
+        assert_eq!(g.generate_one(120 + 1_000_013), "one million thirteen");
+        assert_eq!(g.index_of("two million five hundred"), Some(2_000_620));
+        assert_eq!(g.index_of("one hundred and one"), None);
+        assert_eq!(g.index_of("two million five hundred one"), None);
+        let re = Regex::new(&format!("^(?:{})$", g.regex())).unwrap();
+        for i in (0..g.len()).step_by(997) {
+            assert!(re.is_match(&g.generate_one(i)));
+        }
+        assert!(!re.is_match("minus zero"));
+        assert!(!re.is_match("twenty-zero"));
+        assert!(!re.is_match("two million five hundred one"));
+        let spelled: Vec<_> = Generator::number_words(0..=1100).generate_all().collect();
+        assert_values(&Generator::number_words(0..=1100), spelled);
+
+        let g = Generator::roman_numerals(1..=3999);
+        let numerals: Vec<_> = g.generate_all().collect();
+        assert_eq!(numerals[1993], "MCMXCIV");
+        assert_values(&g, numerals);
+        let g = Generator::roman_numerals(14..=2024);
+        let numerals: Vec<_> = g.generate_all().collect();
+        assert_eq!(numerals[0], "XIV");
+        assert_values(&g, numerals);
+    }
+
+    #[test]
+    fn decimals() {
+        assert_values(
+            &Generator::decimal(-2.5, 3.0, 1),
+            (-25..=30).map(|n| format!("{:.1}", f64::from(n) / 10.0)),
+        );
+        let g = Generator::decimal(0.1, 0.29, 1);
+        assert_eq!(g.generate_all().collect::<Vec<_>>(), ["0.1", "0.2"]);
+        let g = Generator::decimal(-0.15, -0.05, 1);
+        assert_eq!(g.generate_all().collect::<Vec<_>>(), ["-0.1"]);
+        let g = Generator::decimal(1.1, 1.1, 2);
+        assert_eq!(g.generate_all().collect::<Vec<_>>(), ["1.10"]);
+
+        let format = IntFormat {
+            width: 3,
+            separator: Some('.'),
+            decimal_point: ',',
+            plus_sign: true,
+            ..Default::default()
+        };
+        let g = Generator::decimal_with(-5.0, 12_345.678, 3, format);
+        assert_eq!(g.generate_one(0), "-005,000");
+        assert_eq!(g.generate_one(5000), "+000,000");
+        assert_eq!(g.generate_one(5000 + 12_345_678), "+12.345,678");
+        assert_eq!(g.index_of("+1.234,500"), Some(5000 + 1_234_500));
+        assert_eq!(g.index_of("+1234,500"), None);
+        let re = Regex::new(&format!("^(?:{})$", g.regex())).unwrap();
+        for i in (0..g.len()).step_by(9973) {
+            assert!(re.is_match(&g.generate_one(i)));
+        }
+        assert!(!re.is_match("-000,000"));
+        assert!(!re.is_match("+12.345,679"));
+
+        let format = IntFormat {
+            notation: Notation::Scientific,
+            ..Default::default()
+        };
+        let g = Generator::decimal_with(-0.5, 12.0, 2, format);
+        assert_values(
+            &g,
+            (-50..=1200i32).map(|n| {
+                if n == 0 {
+                    return "0e0".to_string();
+                }
+                let digits = n.unsigned_abs().to_string();
+                let sign = if n < 0 { "-" } else { "" };
+                let (first, rest) = digits.split_at(1);
+                let rest = rest.trim_end_matches('0');
+                let point = if rest.is_empty() { "" } else { "." };
+                format!("{sign}{first}{point}{rest}e{}", digits.len() as i32 - 3)
+            }),
+        );
+        let re = Regex::new(&format!("^(?:{})$", g.regex())).unwrap();
+        assert!((0..g.len()).all(|i| re.is_match(&g.generate_one(i))));
+        for s in ["0e-2", "0.0e0", "1.0e0", "1.20e1", "5.01e-1", "1.21e1"] {
+            assert!(!re.is_match(s), "{s}");
+            assert_eq!(g.index_of(s), None, "{s}");
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn decimal_without_values() {
+        Generator::decimal(0.01, 0.09, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn roman_zero() {
+        Generator::roman_numerals(0..=10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn digit_separator() {
+        let format = IntFormat {
+            radix: 16,
+            separator: Some('a'),
+            ..Default::default()
+        };
+        Generator::int_range_with(0..=10, format);
+    }
+}
@@ -1,4 +1,6 @@
 use crate::Generator;
+#[cfg(feature = "bigint")]
+use num_bigint::BigUint;
 
 /// Provides iterable access to the range of values represented by the [`Generator`]
 pub struct StringIter<'a> {
@@ -25,21 +27,84 @@ impl<'a> Iterator for StringIter<'a> {
     }
 }
 
-#[cfg(feature = "with_rand")]
 impl<'a> StringIter<'a> {
+    /// Creates an iterator over the `n` values of `c`.
+    pub(crate) fn new(c: &'a Generator, n: u128) -> Self {
+        Self { c, n, i: 0 }
+    }
+
     /// Generates a random value in the [`Generator`]'s domain
+    #[cfg(feature = "with_rand")]
     pub fn random(&self) -> String {
-        let num = rand::random::<u128>() % self.n;
+        self.random_with(&mut rand::thread_rng())
+    }
+
+    /// Generates a random value in the [`Generator`]'s domain, using `rng` as the source of
+    /// randomness
+    #[cfg(feature = "with_rand")]
+    pub fn random_with<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> String {
+        let num = crate::random::index_below(rng, self.n);
         self.c.generate_one(num)
     }
 }
 
 impl<'a> From<&'a Generator> for StringIter<'a> {
+    fn from(c: &'a Generator) -> Self {
+        Self::new(c, c.len())
+    }
+}
+
+/// Provides iterable access to the range of values represented by the [`Generator`], even if
+/// there are more than `u128::MAX` of them.
+#[cfg(feature = "bigint")]
+pub struct BigStringIter<'a> {
+    /// The generator to be used
+    c: &'a Generator,
+
+    /// The total number of values for `c`, equal to `c.len_big()`
+    n: BigUint,
+
+    /// The current value of the iterator. The first value is 0, the last is `n-1`.
+    i: BigUint,
+}
+
+#[cfg(feature = "bigint")]
+impl<'a> Iterator for BigStringIter<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.i == self.n {
+            None
+        } else {
+            let value = self.c.generate_index_big(&self.i, &self.n);
+            self.i += 1u8;
+            Some(value)
+        }
+    }
+}
+
+#[cfg(all(feature = "bigint", feature = "with_rand"))]
+impl<'a> BigStringIter<'a> {
+    /// Generates a random value in the [`Generator`]'s domain
+    pub fn random(&self) -> String {
+        self.random_with(&mut rand::thread_rng())
+    }
+
+    /// Generates a random value in the [`Generator`]'s domain, using `rng` as the source of
+    /// randomness
+    pub fn random_with<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> String {
+        let num = crate::random::big_index_below(rng, &self.n);
+        self.c.generate_index_big(&num, &self.n)
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl<'a> From<&'a Generator> for BigStringIter<'a> {
     fn from(c: &'a Generator) -> Self {
         Self {
             c,
-            n: c.len(),
-            i: 0,
+            n: c.len_big(),
+            i: BigUint::default(),
         }
     }
 }
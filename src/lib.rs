@@ -47,7 +47,7 @@
 //! use generator_combinator::{Generator, oneof, gen};
 //! let space = Generator::from(' ');
 //!
-//! let number = (Generator::Digit * (3, 5)).transform(|s| s.trim_start_matches('0').to_string());
+//! let number = (Generator::Digit * (3, 5)).transform(|s: String| s.trim_start_matches('0').to_string());
 //!
 //! let directional = space.clone() + oneof!("N", "E", "S", "W", "NE", "SE", "SW", "NW");
 //! let street_names = space.clone() + oneof!("Boren", "Olive", "Spring", "Cherry", "Seneca", "Yesler", "Madison", "James", "Union", "Mercer");
@@ -75,10 +75,59 @@ extern crate quickcheck;
 
 mod macros;
 
+mod error;
+pub use error::{CardinalityError, CharClassError, DateTimeError, RegexError, SetOperationError};
+
+mod char_class;
+pub use char_class::CharClass;
+
+mod datetime;
+pub use datetime::DateTimeRange;
+
+mod filter;
+pub use filter::Filter;
+
 mod generator;
 pub use generator::Generator;
 
+mod index;
+
+mod int_range;
+pub use int_range::{IntFormat, IntRange, Notation};
+
 mod iter;
+#[cfg(feature = "bigint")]
+pub use iter::BigStringIter;
 pub use iter::StringIter;
 
+#[cfg(feature = "bigint")]
+pub use num_bigint::BigUint;
+
+mod automaton;
+pub use automaton::Automaton;
+
+mod numerals;
+
+mod parse;
+
+mod visit_iter;
+pub use visit_iter::{Visit, VisitIter};
+
+mod shuffle;
+pub use shuffle::ShuffledIter;
+
+#[cfg(feature = "with_rand")]
+mod random;
+#[cfg(feature = "with_rand")]
+pub use random::SampleIter;
+
+#[cfg(feature = "from_regex")]
+mod from_regex;
+#[cfg(feature = "from_regex")]
+pub use from_regex::FromRegexError;
+
+mod to_regex;
+pub use to_regex::{Dialect, RegexOptions};
+
 mod transformfn;
+pub use transformfn::{BuiltinTransform, TransformContext, TransformFn};
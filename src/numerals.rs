@@ -0,0 +1,444 @@
+//! English number words, ordinal suffixes, and Roman numerals, for [`Notation`].
+use crate::int_range::{one_of, sequence};
+use crate::Generator;
+#[cfg(doc)]
+use crate::Notation;
+use std::ops::RangeInclusive;
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// The short-scale names of each power of 1000, enough for any `u128`.
+const SCALES: [&str; 13] = [
+    "",
+    "thousand",
+    "million",
+    "billion",
+    "trillion",
+    "quadrillion",
+    "quintillion",
+    "sextillion",
+    "septillion",
+    "octillion",
+    "nonillion",
+    "decillion",
+    "undecillion",
+];
+
+/// The numerals for each digit of the ones, tens, hundreds, and thousands.
+const ROMAN: [&[&str]; 4] = [
+    &["", "I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX"],
+    &["", "X", "XX", "XXX", "XL", "L", "LX", "LXX", "LXXX", "XC"],
+    &["", "C", "CC", "CCC", "CD", "D", "DC", "DCC", "DCCC", "CM"],
+    &["", "M", "MM", "MMM"],
+];
+
+/// The largest number written as a Roman numeral.
+pub(crate) const ROMAN_MAX: u128 = 3999;
+
+/// The longest Roman numeral, for 3888.
+pub(crate) const ROMAN_LONGEST: usize = "MMMDCCCLXXXVIII".len();
+
+/// The English ordinal suffix of `n`, such as the `nd` of `2nd` or the `th` of `12th`.
+pub(crate) fn ordinal_suffix(n: u128) -> &'static str {
+    match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// Appends the English words for `n` to `out`, eg, `one hundred twelve`.
+pub(crate) fn write_words(n: u128, out: &mut String) {
+    if n == 0 {
+        out.push_str(ONES[0]);
+        return;
+    }
+
+    let mut groups = vec![];
+    let mut rest = n;
+    while rest > 0 {
+        groups.push((rest % 1000) as usize);
+        rest /= 1000;
+    }
+    let start = out.len();
+    for (scale, group) in groups.iter().enumerate().rev() {
+        if *group == 0 {
+            continue;
+        }
+        if out.len() > start {
+            out.push(' ');
+        }
+        write_below_thousand(*group, out);
+        if scale > 0 {
+            out.push(' ');
+            out.push_str(SCALES[scale]);
+        }
+    }
+}
+
+/// Appends the words for `n`, from 1 to 999.
+fn write_below_thousand(n: usize, out: &mut String) {
+    let (hundreds, rest) = (n / 100, n % 100);
+    if hundreds > 0 {
+        out.push_str(ONES[hundreds]);
+        out.push_str(" hundred");
+        if rest > 0 {
+            out.push(' ');
+        }
+    }
+    if rest >= 20 {
+        out.push_str(TENS[rest / 10]);
+        if rest % 10 > 0 {
+            out.push('-');
+            out.push_str(ONES[rest % 10]);
+        }
+    } else if rest > 0 {
+        out.push_str(ONES[rest]);
+    }
+}
+
+/// Reads the number that `s` spells out, though not necessarily the way
+/// [`write_words`] would.
+pub(crate) fn parse_words(s: &str) -> Option<u128> {
+    let mut total: u128 = 0;
+    let mut group: u128 = 0;
+    for word in s.split([' ', '-']) {
+        if let Some(n) = ONES.iter().position(|w| *w == word) {
+            group += n as u128;
+        } else if let Some(n) = TENS[2..].iter().position(|w| *w == word) {
+            group += 10 * (n as u128 + 2);
+        } else if word == "hundred" {
+            group = group.checked_mul(100)?;
+        } else if let Some(scale) = SCALES[1..].iter().position(|w| *w == word) {
+            let value = group.checked_mul(1000u128.pow(scale as u32 + 1))?;
+            total = total.checked_add(value)?;
+            group = 0;
+        } else {
+            return None;
+        }
+    }
+    total.checked_add(group)
+}
+
+/// Appends the Roman numeral for `n`, from 1 to [`ROMAN_MAX`].
+pub(crate) fn write_roman(n: u128, out: &mut String) {
+    for (place, numerals) in ROMAN.iter().enumerate().rev() {
+        let digit = n / 10u128.pow(place as u32) % 10;
+        out.push_str(numerals[digit as usize]);
+    }
+}
+
+/// Reads the value of the Roman numeral `s`, though not necessarily the way [`write_roman`]
+/// would write it.
+pub(crate) fn parse_roman(s: &str) -> Option<u128> {
+    let values = s
+        .chars()
+        .map(|c| match c {
+            'I' => Some(1),
+            'V' => Some(5),
+            'X' => Some(10),
+            'L' => Some(50),
+            'C' => Some(100),
+            'D' => Some(500),
+            'M' => Some(1000),
+            _ => None,
+        })
+        .collect::<Option<Vec<i64>>>()?;
+
+    // A numeral before a larger one is subtracted from it
+    let mut total: i64 = 0;
+    for (i, value) in values.iter().enumerate() {
+        match values.get(i + 1) {
+            Some(next) if next > value => total -= value,
+            _ => total = total.checked_add(*value)?,
+        }
+    }
+    u128::try_from(total).ok()
+}
+
+/// Splits the numbers from `lo` to `hi` by `n / unit` and `n % unit` into ranges of each, such
+/// that every pairing of a quotient and remainder in the same part is one of the numbers.
+pub(crate) fn split(
+    lo: u128,
+    hi: u128,
+    unit: u128,
+) -> Vec<(RangeInclusive<u128>, RangeInclusive<u128>)> {
+    let (lo_quotient, lo_rem) = (lo / unit, lo % unit);
+    let (hi_quotient, hi_rem) = (hi / unit, hi % unit);
+    if lo_quotient == hi_quotient {
+        return vec![(lo_quotient..=lo_quotient, lo_rem..=hi_rem)];
+    }
+
+    let max = unit - 1;
+    let mut parts = vec![];
+    let mut middle_lo = lo_quotient;
+    if lo_rem > 0 {
+        parts.push((lo_quotient..=lo_quotient, lo_rem..=max));
+        middle_lo += 1;
+    }
+    let middle_hi = if hi_rem == max {
+        hi_quotient
+    } else {
+        hi_quotient - 1
+    };
+    if middle_lo <= middle_hi {
+        parts.push((middle_lo..=middle_hi, 0..=max));
+    }
+    if hi_rem < max {
+        parts.push((hi_quotient..=hi_quotient, 0..=hi_rem));
+    }
+    parts
+}
+
+/// Matches `words[n]` for each `n` in `range`, where an empty word matches nothing.
+fn alternatives(range: RangeInclusive<u128>, words: &[&str]) -> Generator {
+    one_of(
+        range
+            .map(|n| match words[n as usize] {
+                "" => Generator::Empty,
+                word => Generator::from(word),
+            })
+            .collect(),
+    )
+}
+
+/// Matches the words for each number from `lo` to `hi`.
+pub(crate) fn words(lo: u128, hi: u128) -> Generator {
+    let mut branches = vec![];
+    if lo == 0 {
+        branches.push(Generator::from(ONES[0]));
+    }
+    if hi > 0 {
+        branches.push(positive_words(lo.max(1), hi));
+    }
+    one_of(branches)
+}
+
+/// Matches the words for each number from `lo` to `hi`, grouped by their largest scale.
+fn positive_words(lo: u128, hi: u128) -> Generator {
+    let mut branches = vec![];
+    let mut smallest = 0;
+    if lo == 1 {
+        // Every number below the largest power of 1000 that isn't more than `hi` at once
+        let below = (2..SCALES.len())
+            .take_while(|scales| {
+                1000u128
+                    .checked_pow(*scales as u32)
+                    .is_some_and(|p| p - 1 <= hi)
+            })
+            .last();
+        if let Some(scales) = below {
+            branches.push(below_scales(scales));
+            smallest = scales;
+        }
+    }
+
+    for (i, scale) in SCALES.iter().enumerate().skip(smallest) {
+        let unit = 1000u128.pow(i as u32);
+        let next = unit.checked_mul(1000);
+        let (from, to) = (lo.max(unit), next.map_or(hi, |next| hi.min(next - 1)));
+        if from <= to {
+            if scale.is_empty() {
+                branches.push(below_thousand(from, to));
+            } else {
+                for (count, rest) in split(from, to, unit) {
+                    branches.push(sequence(vec![
+                        below_thousand(*count.start(), *count.end()),
+                        Generator::from(format!(" {scale}")),
+                        rest_words(rest, positive_words),
+                    ]));
+                }
+            }
+        }
+        if next.is_none_or(|next| next > hi) {
+            break;
+        }
+    }
+    one_of(branches)
+}
+
+/// Matches the words for every number from 1 up to 1000 to the power of `scales`.
+///
+/// Each scale's words are either alone or followed by the smaller numbers, which is much shorter
+/// than splitting the numbers by their largest scale, repeating every smaller scale for each one.
+fn below_scales(scales: usize) -> Generator {
+    let mut below = below_thousand(1, 999);
+    for scale in &SCALES[1..scales] {
+        let group = below_thousand(1, 999) + Generator::from(format!(" {scale}"));
+        below = one_of(vec![
+            sequence(vec![(group.clone() + ' ').optional(), below]),
+            group,
+        ]);
+    }
+    below
+}
+
+/// Matches the words for each number from `lo` to `hi`, all from 1 to 999.
+fn below_thousand(lo: u128, hi: u128) -> Generator {
+    let mut branches = vec![];
+    if lo < 100 {
+        branches.push(below_hundred(lo, hi.min(99)));
+    }
+    if hi >= 100 {
+        for (hundreds, rest) in split(lo.max(100), hi, 100) {
+            branches.push(sequence(vec![
+                alternatives(hundreds, &ONES),
+                Generator::from(" hundred"),
+                rest_words(rest, below_hundred),
+            ]));
+        }
+    }
+    one_of(branches)
+}
+
+/// Matches the words for each number from `lo` to `hi`, all from 1 to 99.
+fn below_hundred(lo: u128, hi: u128) -> Generator {
+    let mut branches = vec![];
+    if lo < 20 {
+        branches.push(alternatives(lo..=hi.min(19), &ONES));
+    }
+    if hi >= 20 {
+        for (tens, ones) in split(lo.max(20), hi, 10) {
+            let (lo, hi) = ones.into_inner();
+            let mut ones = Generator::Empty;
+            if hi > 0 {
+                ones = Generator::Char('-') + alternatives(lo.max(1)..=hi, &ONES);
+                if lo == 0 {
+                    ones = ones.optional();
+                }
+            }
+            branches.push(sequence(vec![alternatives(tens, &TENS), ones]));
+        }
+    }
+    one_of(branches)
+}
+
+/// Matches what follows a scale word or `hundred` for each remainder in `range`: nothing for
+/// zero, or a space and the words `f` matches for the rest.
+fn rest_words(range: RangeInclusive<u128>, f: fn(u128, u128) -> Generator) -> Generator {
+    let (lo, hi) = range.into_inner();
+    if hi == 0 {
+        return Generator::Empty;
+    }
+    let rest = Generator::Char(' ') + f(lo.max(1), hi);
+    if lo == 0 {
+        rest.optional()
+    } else {
+        rest
+    }
+}
+
+/// Matches the Roman numeral for each number from `lo` to `hi`, all from 1 to [`ROMAN_MAX`].
+pub(crate) fn roman(lo: u128, hi: u128) -> Generator {
+    roman_places(lo, hi, ROMAN.len() - 1)
+}
+
+/// Matches the numerals for the last `place + 1` digits of each number from `lo` to `hi`.
+fn roman_places(lo: u128, hi: u128, place: usize) -> Generator {
+    if place == 0 {
+        return alternatives(lo..=hi, ROMAN[0]);
+    }
+    let branches = split(lo, hi, 10u128.pow(place as u32))
+        .into_iter()
+        .map(|(digit, rest)| {
+            sequence(vec![
+                alternatives(digit, ROMAN[place]),
+                roman_places(*rest.start(), *rest.end(), place - 1),
+            ])
+        })
+        .collect();
+    one_of(branches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words_of(n: u128) -> String {
+        let mut s = String::new();
+        write_words(n, &mut s);
+        s
+    }
+
+    fn roman_of(n: u128) -> String {
+        let mut s = String::new();
+        write_roman(n, &mut s);
+        s
+    }
+
+    #[test]
+    fn spelling() {
+        assert_eq!(words_of(0), "zero");
+        assert_eq!(words_of(15), "fifteen");
+        assert_eq!(words_of(40), "forty");
+        assert_eq!(words_of(99), "ninety-nine");
+        assert_eq!(words_of(112), "one hundred twelve");
+        assert_eq!(words_of(1_000_001), "one million one");
+        assert_eq!(
+            words_of(20_300_405),
+            "twenty million three hundred thousand four hundred five"
+        );
+        assert!(words_of(u128::MAX).starts_with("three hundred forty undecillion "));
+        for n in [0, 7, 19, 20, 101, 110, 999_999, 1_000_000, u128::MAX] {
+            assert_eq!(parse_words(&words_of(n)), Some(n));
+        }
+        assert_eq!(parse_words("one two"), Some(3));
+        assert_eq!(parse_words("one  two"), None);
+
+        assert_eq!(roman_of(4), "IV");
+        assert_eq!(roman_of(1994), "MCMXCIV");
+        assert_eq!(roman_of(3888).len(), ROMAN_LONGEST);
+        for n in 1..=ROMAN_MAX {
+            assert_eq!(parse_roman(&roman_of(n)), Some(n));
+        }
+        assert_eq!(parse_roman("IIII"), Some(4));
+        assert_eq!(parse_roman("IA"), None);
+
+        let suffixes: Vec<_> = [1, 2, 3, 4, 11, 12, 13, 21, 102, 111, 1013]
+            .iter()
+            .map(|n| ordinal_suffix(*n))
+            .collect();
+        assert_eq!(
+            suffixes,
+            ["st", "nd", "rd", "th", "th", "th", "th", "st", "nd", "th", "th"]
+        );
+    }
+
+    #[test]
+    fn split_ranges() {
+        assert_eq!(split(5, 8, 10), [(0..=0, 5..=8)]);
+        assert_eq!(split(0, 99, 10), [(0..=9, 0..=9)]);
+        assert_eq!(
+            split(15, 234, 100),
+            [(0..=0, 15..=99), (1..=1, 0..=99), (2..=2, 0..=34)]
+        );
+        assert_eq!(split(100, 299, 100), [(1..=2, 0..=99)]);
+    }
+}
@@ -0,0 +1,505 @@
+//! Maps strings back to the indices of the [`Generator`] values that produce them.
+use crate::error::CardinalityError;
+use crate::index::Index;
+use crate::Generator;
+use std::collections::HashMap;
+
+/// A partial parse: the byte offset where it ends and the index of the parsed value.
+type Match<I> = (usize, I);
+
+/// An [`Index`] that discards its value, for parsing when only the positions of matches matter.
+///
+/// Since all `Unindexed` values are equal, deduplicating matches reduces them to the distinct
+/// positions reached, and parsing never overflows however large the generator is.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Unindexed;
+
+impl Index for Unindexed {
+    const INDEXED: bool = false;
+
+    fn from_u128(_n: u128) -> Self {
+        Unindexed
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        None
+    }
+
+    fn checked_add(&self, _rhs: &Self) -> Option<Self> {
+        Some(Unindexed)
+    }
+
+    fn checked_mul(&self, _rhs: &Self) -> Option<Self> {
+        Some(Unindexed)
+    }
+
+    fn checked_pow(&self, _exp: usize) -> Option<Self> {
+        Some(Unindexed)
+    }
+
+    fn sub(&self, _rhs: &Self) -> Self {
+        Unindexed
+    }
+
+    fn div_rem(&self, _rhs: &Self) -> (Self, Self) {
+        (Unindexed, Unindexed)
+    }
+}
+
+impl Generator {
+    /// Whether this `Generator` can produce `s`.
+    ///
+    /// Unlike matching against [`regex`](Self::regex), this is exact. As with
+    /// [`index_of`](Self::index_of), values produced by a [`Transform`](Self::Transform) never
+    /// match, unless it's a [`BuiltinTransform`](crate::BuiltinTransform) that can be inverted.
+    ///
+    /// ```
+    /// use generator_combinator::{oneof, Generator};
+    /// let g = oneof!("foo", "bar") + Generator::Digit * (1, 2);
+    /// assert!(g.matches("foo7"));
+    /// assert!(g.matches("bar42"));
+    /// assert!(!g.matches("bar123"));
+    /// assert!(!g.matches("baz1"));
+    /// ```
+    pub fn matches(&self, s: &str) -> bool {
+        !self.full_parses::<Unindexed>(s).is_empty()
+    }
+
+    /// Splits `s` into the parts that [`visit_one`](Self::visit_one) would pass to its callback
+    /// for the index of `s`, or `None` if this `Generator` can't produce `s`.
+    ///
+    /// If the generator is ambiguous, the parts are those of the smallest index producing `s`.
+    /// As with [`index_of`](Self::index_of), values of a [`Filter`](crate::Filter) whose count is
+    /// estimated have no index, so they give `None`.
+    ///
+    /// Panics if this generator has more than `u128::MAX` values and the `bigint` feature isn't
+    /// enabled, since the smallest index can't be found; see [`try_parse`](Self::try_parse).
+    ///
+    /// ```
+    /// use generator_combinator::{oneof, Generator};
+    /// let g = oneof!("N", "S") + ' ' + Generator::Digit * (1, 3);
+    /// assert_eq!(
+    ///     g.parse("N 42"),
+    ///     Some(vec!["N".to_string(), " ".into(), "4".into(), "2".into()])
+    /// );
+    /// assert_eq!(g.parse("E 42"), None);
+    /// ```
+    pub fn parse(&self, s: &str) -> Option<Vec<String>> {
+        match self.try_parse(s) {
+            Ok(parts) => parts,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Like [`parse`](Self::parse), but returns [`CardinalityError::Overflow`] rather than
+    /// panicking if this generator has more than `u128::MAX` values and the `bigint` feature isn't
+    /// enabled.
+    pub fn try_parse(&self, s: &str) -> Result<Option<Vec<String>>, CardinalityError> {
+        if self.try_len().is_ok() {
+            return Ok(self.parse_parts::<u128>(s));
+        }
+
+        #[cfg(feature = "bigint")]
+        return Ok(self.parse_parts::<num_bigint::BigUint>(s));
+
+        #[cfg(not(feature = "bigint"))]
+        Err(CardinalityError::Overflow)
+    }
+
+    /// Visits the parts of the smallest index producing `s`. The cardinality of this generator
+    /// must fit in `I`.
+    fn parse_parts<I: Index>(&self, s: &str) -> Option<Vec<String>> {
+        let mut num: I = self.full_parses(s).into_iter().next()?;
+        let mut parts = vec![];
+        self.visit_exact_inner(&mut num, &mut String::new(), &mut |part| {
+            parts.push(part.to_string())
+        });
+        Some(parts)
+    }
+
+    /// Finds the index that [`generate_one`](Self::generate_one) would turn into `s`, or `None` if
+    /// this `Generator` can't produce `s`.
+    ///
+    /// If the generator is ambiguous and produces `s` from several indices, the smallest is
+    /// returned; see [`indices_of`](Self::indices_of) to get all of them.
+    ///
+    /// Values produced by a [`Transform`](Self::Transform) can't be mapped back to an index. The
+    /// exceptions are the [`BuiltinTransform`](crate::BuiltinTransform)s `Reverse`, `Prefix`,
+    /// `Suffix`, `PadStart`, and `PadEnd`, which can be undone. Nor do the values of a
+    /// [`Filter`](crate::Filter) whose count is estimated have an index, though
+    /// [`matches`](Self::matches) recognizes them.
+    ///
+    /// Panics if this generator has more than `u128::MAX` values; see
+    /// [`try_indices_of`](Self::try_indices_of), or [`index_of_big`](Self::index_of_big) with the
+    /// `bigint` feature.
+    ///
+    /// ```
+    /// use generator_combinator::{oneof, Generator};
+    /// let g = oneof!("foo", "bar", "baz") + Generator::Digit * 4;
+    /// assert_eq!(g.index_of("bar1234"), Some(3703));
+    /// assert_eq!(g.generate_one(3703), "bar1234");
+    /// assert_eq!(g.index_of("qux1234"), None);
+    /// ```
+    pub fn index_of(&self, s: &str) -> Option<u128> {
+        self.indices_of(s).into_iter().next()
+    }
+
+    /// Finds every index that [`generate_one`](Self::generate_one) would turn into `s`, in
+    /// ascending order. The result is empty if this `Generator` can't produce `s`.
+    ///
+    /// ```
+    /// use generator_combinator::{oneof, Generator};
+    /// // "aa" can be produced as a single "aa" or as "a" twice
+    /// let g = oneof!("a", "aa") * (1, 2);
+    /// assert_eq!(g.indices_of("aa"), vec![1, 2]);
+    /// assert_eq!(g.generate_one(1), "aa");
+    /// assert_eq!(g.generate_one(2), "aa");
+    /// ```
+    ///
+    /// Panics if this generator has more than `u128::MAX` values; see
+    /// [`try_indices_of`](Self::try_indices_of).
+    pub fn indices_of(&self, s: &str) -> Vec<u128> {
+        match self.try_indices_of(s) {
+            Ok(indices) => indices,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Like [`indices_of`](Self::indices_of), but returns [`CardinalityError::Overflow`] rather
+    /// than panicking if this generator has more than `u128::MAX` values, whose indices don't fit
+    /// in a `u128`.
+    pub fn try_indices_of(&self, s: &str) -> Result<Vec<u128>, CardinalityError> {
+        self.try_len()?;
+        Ok(self.full_parses(s))
+    }
+
+    /// Finds every index of a value equal to `s`, in ascending order. The cardinality of this
+    /// generator must fit in `I`.
+    pub(crate) fn full_parses<I: Index>(&self, s: &str) -> Vec<I> {
+        let mut indices: Vec<I> = self
+            .parse_at(s, 0)
+            .into_iter()
+            .filter(|(end, _)| *end == s.len())
+            .map(|(_, i)| i)
+            .collect();
+        indices.sort();
+        indices.dedup();
+        indices
+    }
+
+    /// Parses a prefix of `s[pos..]`, returning every way this `Generator` can produce it.
+    fn parse_at<I: Index>(&self, s: &str, pos: usize) -> Vec<Match<I>> {
+        use Generator::*;
+        let rest = &s[pos..];
+
+        match self {
+            Char(c) if rest.starts_with(*c) => vec![(pos + c.len_utf8(), I::zero())],
+            Str(t) if rest.starts_with(t.as_str()) => vec![(pos + t.len(), I::zero())],
+            Char(_) | Str(_) => vec![],
+            IntRange(range) => range
+                .parse_prefixes(rest)
+                .into_iter()
+                .map(|(len, offset)| (pos + len, I::from_u128(offset)))
+                .collect(),
+            DateTime(range) => range
+                .parse_prefixes(rest)
+                .into_iter()
+                .map(|(len, offset)| (pos + len, I::from_u128(offset)))
+                .collect(),
+            CharClass(class) => match rest.chars().next().and_then(|c| Some((c, class.rank(c)?))) {
+                Some((c, i)) => vec![(pos + c.len_utf8(), I::from_u128(i as u128))],
+                None => vec![],
+            },
+            OneOf { v, is_optional } => {
+                let mut matches = vec![];
+                let mut offset = I::zero();
+                if *is_optional {
+                    matches.push((pos, I::zero()));
+                    offset = I::from_u128(1);
+                }
+                for a in v {
+                    for (end, i) in a.parse_at::<I>(s, pos) {
+                        matches.push((end, add(&offset, &i)));
+                    }
+                    offset = add(&offset, &a.len_as());
+                }
+                matches
+            }
+            RepeatedN(a, n) => a.parse_repeated(s, pos, *n),
+            RepeatedMN(a, m, n) => {
+                let base: I = a.len_as();
+                let mut matches = vec![];
+                let mut offset = I::zero();
+                for count in *m..=*n {
+                    for (end, i) in a.parse_repeated::<I>(s, pos, count) {
+                        matches.push((end, add(&offset, &i)));
+                    }
+                    offset = add(&offset, &pow(&base, count));
+                }
+                matches
+            }
+            Sequence(v) => {
+                // Earlier components are the less significant 'digits' of the index
+                let mut partials = vec![(pos, I::zero(), I::from_u128(1))];
+                for a in v {
+                    let a_len = a.len_as();
+                    let mut next = vec![];
+                    for (start, acc, scale) in partials {
+                        for (end, i) in a.parse_at::<I>(s, start) {
+                            next.push((end, add(&acc, &mul(&scale, &i)), mul(&scale, &a_len)));
+                        }
+                    }
+                    next.sort();
+                    next.dedup();
+                    partials = next;
+                }
+                partials
+                    .into_iter()
+                    .map(|(end, acc, _)| (end, acc))
+                    .collect()
+            }
+            // Only some built-in transforms can be inverted, by parsing each value that they could
+            // have transformed into every prefix of `rest`
+            Transform {
+                inner,
+                transform_fn,
+            } => {
+                let Some(t) = transform_fn.builtin() else {
+                    return vec![];
+                };
+                let mut matches = vec![];
+                for end in (pos..=s.len()).filter(|end| s.is_char_boundary(*end)) {
+                    let Some(preimages) = t.preimages(&s[pos..end]) else {
+                        return vec![];
+                    };
+                    for preimage in preimages {
+                        for i in inner.full_parses::<I>(&preimage) {
+                            matches.push((end, i));
+                        }
+                    }
+                }
+                matches
+            }
+            // Only exact filters have an index for each kept value, but the values of others can
+            // still be recognized
+            Filter(filter) if filter.is_exact() => filter
+                .inner()
+                .parse_at::<u128>(s, pos)
+                .into_iter()
+                .filter_map(|(end, i)| Some((end, I::from_u128(filter.offset_of(i)?))))
+                .collect(),
+            Filter(filter) if !I::INDEXED => filter
+                .inner()
+                .parse_at::<Unindexed>(s, pos)
+                .into_iter()
+                .filter(|(end, _)| filter.keeps(&s[pos..*end]))
+                .map(|(end, _)| (end, I::zero()))
+                .collect(),
+            Filter(_) => vec![],
+            Automaton(automaton) => automaton
+                .parse_prefixes(rest)
+                .into_iter()
+                .map(|(len, i)| (pos + len, i))
+                .collect(),
+            Empty => vec![(pos, I::zero())],
+
+            // Built-in character classes
+            AlphaLower | AlphaUpper | Digit | AlphaNumLower | AlphaNumUpper | HexUpper
+            | HexLower | Base64 | Base64Url | Base32 | Base58 | PrintableAscii | Punctuation
+            | Whitespace => {
+                let alphabet = self.alphabet().expect("built-in classes have alphabets");
+                match rest.bytes().next() {
+                    Some(b) => match alphabet.iter().position(|a| *a == b) {
+                        Some(i) => vec![(pos + 1, I::from_u128(i as u128))],
+                        None => vec![],
+                    },
+                    None => vec![],
+                }
+            }
+        }
+    }
+
+    /// Parses `count` consecutive values of this `Generator` from `s[pos..]`.
+    fn parse_repeated<I: Index>(&self, s: &str, pos: usize, count: usize) -> Vec<Match<I>> {
+        // The first repetition is the most significant 'digit' of the index
+        let base: I = self.len_as();
+
+        // Repetitions often revisit the same positions, so only parse each one once
+        let mut cache: HashMap<usize, Vec<Match<I>>> = HashMap::new();
+
+        let mut partials = vec![(pos, I::zero())];
+        for _ in 0..count {
+            let mut next = vec![];
+            for (start, acc) in partials {
+                let matches = cache
+                    .entry(start)
+                    .or_insert_with(|| self.parse_at(s, start));
+                for (end, i) in matches.iter() {
+                    next.push((*end, add(&mul(&acc, &base), i)));
+                }
+            }
+            next.sort();
+            next.dedup();
+            partials = next;
+        }
+        partials
+    }
+}
+
+// Indices produced while parsing are bounded by the generator's cardinality, which the caller
+// ensures fits in `I`.
+
+fn add<I: Index>(a: &I, b: &I) -> I {
+    a.checked_add(b).expect("index fits")
+}
+
+fn mul<I: Index>(a: &I, b: &I) -> I {
+    a.checked_mul(b).expect("index fits")
+}
+
+fn pow<I: Index>(a: &I, exp: usize) -> I {
+    a.checked_pow(exp).expect("index fits")
+}
+
+#[cfg(feature = "bigint")]
+impl Generator {
+    /// Finds the index that [`generate_one_big`](Self::generate_one_big) would turn into `s`. See
+    /// [`index_of`](Self::index_of).
+    pub fn index_of_big(&self, s: &str) -> Option<num_bigint::BigUint> {
+        self.indices_of_big(s).into_iter().next()
+    }
+
+    /// Finds every index that [`generate_one_big`](Self::generate_one_big) would turn into `s`.
+    /// See [`indices_of`](Self::indices_of).
+    pub fn indices_of_big(&self, s: &str) -> Vec<num_bigint::BigUint> {
+        self.full_parses(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{gen, oneof, CardinalityError, Generator};
+
+    /// Checks that every value maps back to its own index, and only to indices of equal values.
+    fn assert_round_trips(g: &Generator) {
+        for (i, s) in g.generate_all().enumerate() {
+            let indices = g.indices_of(&s);
+            assert!(indices.contains(&(i as u128)), "{s:?} didn't map to {i}");
+            for j in indices {
+                assert_eq!(g.generate_one(j), s);
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        assert_round_trips(&(oneof!("foo", "bar", "baz") + Generator::Digit * 2));
+        assert_round_trips(&(Generator::HexUpper * (1, 3)));
+        assert_round_trips(&(gen!("x") * (2, 4)));
+        assert_round_trips(&(oneof!('a', 'b') * (0, 3) + '-' + Generator::AlphaNumLower));
+        assert_round_trips(
+            &(gen!("iris") + (gen!(' ') + oneof!("versicolor", "setosa")).optional()),
+        );
+        assert_round_trips(&((oneof!('x', 'y') * (1, 2)).optional() * 2 + Generator::AlphaUpper));
+        assert_round_trips(&(Generator::Empty + gen!("é") + oneof!('ü', 'ñ') * 2));
+    }
+
+    #[test]
+    fn ambiguous() {
+        // Each of "", "a", and "aa" can be produced by either repetition
+        let g = gen!('a').optional() * 2;
+        assert_eq!(g.len(), 4);
+        assert_eq!(g.indices_of(""), vec![0]);
+        assert_eq!(g.indices_of("a"), vec![1, 2]);
+        assert_eq!(g.indices_of("aa"), vec![3]);
+        assert_eq!(g.index_of("a"), Some(1));
+
+        let g = oneof!("ab", "a") + oneof!("c", "bc");
+        assert_eq!(g.indices_of("abc"), vec![0, 3]);
+        assert_round_trips(&g);
+    }
+
+    #[test]
+    fn no_match() {
+        let g = oneof!("foo", "bar") + Generator::Digit * 2;
+        assert_eq!(g.index_of("foo1"), None);
+        assert_eq!(g.index_of("foo123"), None);
+        assert_eq!(g.index_of("fooab"), None);
+        assert_eq!(g.index_of(""), None);
+
+        // Transforms can't be inverted
+        let g = (Generator::Digit * 3).transform(|s: String| s.trim_start_matches('0').to_string());
+        assert_eq!(g.index_of("123"), None);
+
+        // The indices of generators too large for a u128 don't fit in one
+        let g = Generator::Digit * 39;
+        assert_eq!(
+            g.try_indices_of(&"0".repeat(39)),
+            Err(CardinalityError::Overflow)
+        );
+        assert_eq!(g.try_indices_of("0"), Err(CardinalityError::Overflow));
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_overflow() {
+        (Generator::Digit * 39).index_of(&"0".repeat(39));
+    }
+
+    #[test]
+    fn matches() {
+        let g = oneof!("foo", "bar") + Generator::Digit * (1, 2);
+        for s in g.generate_all() {
+            assert!(g.matches(&s));
+        }
+        for s in ["", "foo", "foo123", "baz1", "foo1 ", "FOO1"] {
+            assert!(!g.matches(s));
+        }
+
+        // Matching doesn't depend on indices fitting in a u128
+        let g = Generator::AlphaNumLower * 40 + oneof!(".com", ".net");
+        assert!(g.try_len().is_err());
+        assert!(g.matches(&format!("{}.net", "z".repeat(40))));
+        assert!(!g.matches(&format!("{}.org", "z".repeat(40))));
+        assert!(!g.matches(&format!("{}.net", "z".repeat(39))));
+
+        // Highly ambiguous generators only need to track the positions reached
+        let g = oneof!("a", "aa").optional() * 60;
+        assert!(g.matches(&"a".repeat(120)));
+        assert!(!g.matches(&"a".repeat(121)));
+    }
+
+    #[test]
+    fn parse() {
+        let g = oneof!("foo", "bar", "baz") + Generator::Digit * 4;
+        for (i, s) in g.generate_all().enumerate().step_by(97) {
+            let mut parts = vec![];
+            g.visit_one(i as u128, |part| parts.push(part));
+            assert_eq!(g.parse(&s), Some(parts));
+        }
+        assert_eq!(g.parse("bar123"), None);
+
+        // Ambiguous values are split as the smallest index would be
+        let g = oneof!("a", "aa") * (1, 2);
+        assert_eq!(g.parse("aa"), Some(vec!["aa".to_string()]));
+
+        // Larger generators need arbitrary-precision indices
+        let g = Generator::Digit * 39;
+        let s = "7".repeat(39);
+        assert!(g.matches(&s));
+        #[cfg(feature = "bigint")]
+        assert_eq!(g.try_parse(&s), Ok(Some(vec!["7".to_string(); 39])));
+        #[cfg(not(feature = "bigint"))]
+        assert_eq!(g.try_parse(&s), Err(crate::CardinalityError::Overflow));
+    }
+
+    #[test]
+    #[cfg(feature = "bigint")]
+    fn bigint() {
+        use crate::BigUint;
+        let g = Generator::Digit * 39;
+        let num = BigUint::from(10u8).pow(38) * 7u8 + 42u8;
+        let s = g.generate_one_big(&num);
+        assert_eq!(g.index_of_big(&s), Some(num));
+    }
+}
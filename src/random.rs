@@ -0,0 +1,403 @@
+//! Random sampling of [`Generator`] values using any [`Rng`].
+use crate::index::Index;
+use crate::{CardinalityError, Generator};
+use rand::distributions::Distribution;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// Selects a random index in `0..n`.
+///
+/// Every index is equally likely; unlike taking a random `u128` modulo `n`, this doesn't favor
+/// the lower indices when `n` isn't a power of two.
+pub(crate) fn index_below<R: Rng + ?Sized>(rng: &mut R, n: u128) -> u128 {
+    rng.gen_range(0..n)
+}
+
+/// Selects a random index in `0..n`.
+#[cfg(feature = "bigint")]
+pub(crate) fn big_index_below<R: Rng + ?Sized>(
+    rng: &mut R,
+    n: &num_bigint::BigUint,
+) -> num_bigint::BigUint {
+    use num_bigint::RandBigInt;
+    rng.gen_biguint_below(n)
+}
+
+/// Samples values uniformly from a `Generator`'s range, so that with a seeded [`Rng`] the same
+/// values are produced every time:
+///
+/// ```
+/// use generator_combinator::{oneof, Generator};
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+/// let g = oneof!("foo", "bar", "baz") + Generator::Digit * 4;
+///
+/// let first: Vec<String> = StdRng::seed_from_u64(42).sample_iter(&g).take(5).collect();
+/// let second: Vec<String> = StdRng::seed_from_u64(42).sample_iter(&g).take(5).collect();
+/// assert_eq!(first, second);
+/// ```
+///
+/// Panics if the `Generator` has no values, or if it has more than `u128::MAX` values and the
+/// `bigint` feature isn't enabled.
+impl Distribution<String> for Generator {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        #[cfg(feature = "bigint")]
+        if self.try_len().is_err() {
+            let len = self.len_big();
+            return self.generate_index_big(&big_index_below(rng, &len), &len);
+        }
+
+        self.generate_one(index_below(rng, self.len()))
+    }
+}
+
+impl Generator {
+    /// Provides an endless iterator of random values from this `Generator`, using `rng` as the
+    /// source of randomness.
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    /// let g = Generator::HexLower * 8;
+    ///
+    /// let ids: Vec<String> = g.sample_iter(StdRng::seed_from_u64(7)).take(3).collect();
+    /// assert_eq!(ids.len(), 3);
+    /// assert!(ids.iter().all(|id| g.matches(id)));
+    /// ```
+    pub fn sample_iter<R: Rng>(&self, rng: R) -> SampleIter<'_, R> {
+        SampleIter { c: self, rng }
+    }
+
+    /// Selects `k` distinct random values from this `Generator`, in random order.
+    ///
+    /// Indices are chosen with Floyd's algorithm, so this takes time and memory proportional to
+    /// `k` regardless of how many values the generator represents or how close `k` is to that
+    /// number. An ambiguous generator, a transform, or an estimated [`Filter`](crate::Filter) may
+    /// produce equal values from distinct indices; more indices are then drawn until there are `k`
+    /// distinct values, so a value produced by several indices is more likely to be selected.
+    ///
+    /// Returns [`CardinalityError::NotEnoughValues`] if the generator has fewer than `k` distinct
+    /// values. Without the `bigint` feature, returns [`CardinalityError::Overflow`] if the number
+    /// of indices exceeds `u128::MAX`.
+    ///
+    /// ```
+    /// use generator_combinator::{CardinalityError, Generator};
+    /// use rand::{rngs::StdRng, SeedableRng};
+    /// let mut rng = StdRng::seed_from_u64(1);
+    ///
+    /// let all = Generator::Digit.sample_distinct(10, &mut rng).unwrap();
+    /// assert_eq!(all.len(), 10);
+    ///
+    /// assert_eq!(
+    ///     Generator::Digit.sample_distinct(11, &mut rng),
+    ///     Err(CardinalityError::NotEnoughValues { requested: 11, len: 10 })
+    /// );
+    /// ```
+    pub fn sample_distinct<R: Rng + ?Sized>(
+        &self,
+        k: usize,
+        rng: &mut R,
+    ) -> Result<Vec<String>, CardinalityError> {
+        match self.try_len() {
+            Ok(n) => self.sample_distinct_below(n, k, rng),
+            #[cfg(feature = "bigint")]
+            Err(_) => self.sample_distinct_below(self.len_big(), k, rng),
+            #[cfg(not(feature = "bigint"))]
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Selects `k` distinct random values from this `Generator`, whose `n` indices fit in `I`.
+    fn sample_distinct_below<I: RandomIndex, R: Rng + ?Sized>(
+        &self,
+        n: I,
+        k: usize,
+        rng: &mut R,
+    ) -> Result<Vec<String>, CardinalityError> {
+        let one = I::from_u128(1);
+        let not_enough = |len| CardinalityError::NotEnoughValues { requested: k, len };
+        let k_big = I::from_u128(k as u128);
+        if k_big > n {
+            return Err(not_enough(n.to_u128().expect("n is less than k")));
+        }
+
+        let mut chosen = HashSet::with_capacity(k);
+        let mut indices = Vec::with_capacity(k);
+        let mut j = n.sub(&k_big);
+        while j < n {
+            let t = I::random_below(rng, &add(&j, &one));
+            let i = if chosen.contains(&t) { j.clone() } else { t };
+            chosen.insert(i.clone());
+            indices.push(i);
+            j = add(&j, &one);
+        }
+
+        let mut seen = HashSet::with_capacity(k);
+        let mut values = Vec::with_capacity(k);
+        for i in indices {
+            let s = self.generate_index(i);
+            if seen.insert(s.clone()) {
+                values.push(s);
+            }
+        }
+
+        // Replace values produced more than once with those of further indices. Once most indices
+        // have been tried, the rest are shuffled rather than found by rejection.
+        let mut rest: Option<Vec<I>> = None;
+        while values.len() < k {
+            let i = if I::from_u128(2 * chosen.len() as u128) < n {
+                loop {
+                    let t = I::random_below(rng, &n);
+                    if chosen.insert(t.clone()) {
+                        break t;
+                    }
+                }
+            } else {
+                let rest = rest.get_or_insert_with(|| {
+                    let mut rest = vec![];
+                    let mut i = I::zero();
+                    while i < n {
+                        if !chosen.contains(&i) {
+                            rest.push(i.clone());
+                        }
+                        i = add(&i, &one);
+                    }
+                    rest.shuffle(rng);
+                    rest
+                });
+                match rest.pop() {
+                    Some(i) => i,
+                    None => return Err(not_enough(values.len() as u128)),
+                }
+            };
+            let s = self.generate_index(i);
+            if seen.insert(s.clone()) {
+                values.push(s);
+            }
+        }
+
+        // Floyd's algorithm chooses a uniformly random subset, but not in a uniformly random order
+        values.shuffle(rng);
+        Ok(values)
+    }
+}
+
+/// An [`Index`] that can be selected at random.
+trait RandomIndex: Index + Hash {
+    /// Selects a random index in `0..n`.
+    fn random_below<R: Rng + ?Sized>(rng: &mut R, n: &Self) -> Self;
+}
+
+impl RandomIndex for u128 {
+    fn random_below<R: Rng + ?Sized>(rng: &mut R, n: &Self) -> Self {
+        index_below(rng, *n)
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl RandomIndex for num_bigint::BigUint {
+    fn random_below<R: Rng + ?Sized>(rng: &mut R, n: &Self) -> Self {
+        big_index_below(rng, n)
+    }
+}
+
+fn add<I: Index>(a: &I, b: &I) -> I {
+    a.checked_add(b).expect("bounded by the generator's length")
+}
+
+/// An endless iterator of random values from a [`Generator`], created by
+/// [`Generator::sample_iter`].
+#[derive(Debug)]
+pub struct SampleIter<'a, R> {
+    c: &'a Generator,
+    rng: R,
+}
+
+impl<'a, R: Rng> Iterator for SampleIter<'a, R> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.c.sample(&mut self.rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{gen, oneof, CardinalityError, Generator};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn reproducible() {
+        let g = oneof!("foo", "bar", "baz") + Generator::AlphaNumUpper * (2, 6);
+
+        let values = g.generate_all();
+        let mut rng1 = StdRng::seed_from_u64(1234);
+        let mut rng2 = StdRng::seed_from_u64(1234);
+        for _ in 0..100 {
+            assert_eq!(values.random_with(&mut rng1), values.random_with(&mut rng2));
+        }
+
+        let visits = g.visit_all();
+        for _ in 0..100 {
+            let v1 = visits.random_with(&mut rng1);
+            let v2 = visits.random_with(&mut rng2);
+            assert_eq!(v1.index(), v2.index());
+        }
+
+        let samples1: Vec<_> = g.sample_iter(StdRng::seed_from_u64(99)).take(100).collect();
+        let samples2: Vec<_> = g.sample_iter(StdRng::seed_from_u64(99)).take(100).collect();
+        assert_eq!(samples1, samples2);
+        assert!(samples1.iter().all(|s| g.matches(s)));
+
+        // A different seed gives different values
+        let samples3: Vec<_> = g
+            .sample_iter(StdRng::seed_from_u64(100))
+            .take(100)
+            .collect();
+        assert_ne!(samples1, samples3);
+    }
+
+    /// Pearson's chi-squared statistic for `counts` against a uniform distribution.
+    fn chi_squared(counts: &[u64]) -> f64 {
+        let total: u64 = counts.iter().sum();
+        let expected = total as f64 / counts.len() as f64;
+        counts
+            .iter()
+            .map(|&observed| (observed as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    #[test]
+    fn uniform() {
+        let mut rng = StdRng::seed_from_u64(2022);
+
+        // Small generators whose lengths aren't powers of two, with the critical values for 2 and
+        // 109 degrees of freedom at p = 0.001
+        for (g, critical) in [
+            (oneof!("a", "b", "c"), 13.816),
+            (Generator::Digit * (1, 2), 160.4),
+        ] {
+            let values = g.generate_all();
+            let mut counts: HashMap<String, u64> = HashMap::new();
+            for _ in 0..100 * g.len() {
+                *counts.entry(values.random_with(&mut rng)).or_default() += 1;
+            }
+            assert_eq!(counts.len() as u128, g.len());
+            let counts: Vec<u64> = counts.into_values().collect();
+            assert!(chi_squared(&counts) < critical, "{g:?}: {counts:?}");
+        }
+
+        // An index range that's not a power of two, where modulo bias would be at its worst: a
+        // random u128 modulo `n` would fall in the lower half twice as often as the upper half
+        let n = u128::MAX / 3 * 2;
+        let mut halves = [0u64; 2];
+        for _ in 0..100_000 {
+            let i = super::index_below(&mut rng, n);
+            assert!(i < n);
+            halves[(i >= n / 2) as usize] += 1;
+        }
+        // The critical value for 1 degree of freedom at p = 0.001
+        assert!(chi_squared(&halves) < 10.828, "{halves:?}");
+    }
+
+    #[test]
+    fn distinct() {
+        let mut rng = StdRng::seed_from_u64(5);
+
+        let g = oneof!("foo", "bar") + Generator::Digit * 2;
+        for k in [0, 1, 100, 199, 200] {
+            let values = g.sample_distinct(k, &mut rng).unwrap();
+            assert_eq!(values.len(), k);
+            let unique: HashSet<_> = values.iter().collect();
+            assert_eq!(unique.len(), k);
+            assert!(values.iter().all(|s| g.matches(s)));
+        }
+
+        assert_eq!(
+            g.sample_distinct(201, &mut rng),
+            Err(CardinalityError::NotEnoughValues {
+                requested: 201,
+                len: 200
+            })
+        );
+        #[cfg(not(feature = "bigint"))]
+        assert_eq!(
+            (Generator::Digit * 39).sample_distinct(1, &mut rng),
+            Err(CardinalityError::Overflow)
+        );
+
+        // "a" is produced by two indices, but is only selected once
+        let g = gen!('a').optional() * 2;
+        for _ in 0..20 {
+            let mut values = g.sample_distinct(3, &mut rng).unwrap();
+            values.sort();
+            assert_eq!(values, ["", "a", "aa"]);
+        }
+        assert_eq!(
+            g.sample_distinct(4, &mut rng),
+            Err(CardinalityError::NotEnoughValues {
+                requested: 4,
+                len: 3
+            })
+        );
+        // Transforms can also produce equal values, such as "7" from both "7" and "07"
+        let g = (Generator::Digit * (1, 2))
+            .transform(|s: String| s.trim_start_matches('0').to_string());
+        assert_eq!(g.len(), 110);
+        let values = g.sample_distinct(100, &mut rng).unwrap();
+        assert_eq!(values.iter().collect::<HashSet<_>>().len(), 100);
+        assert!(g.sample_distinct(101, &mut rng).is_err());
+
+        // Works without enumerating even the largest generators
+        let g = Generator::Digit * 38;
+        let values = g.sample_distinct(10_000, &mut rng).unwrap();
+        let unique: HashSet<_> = values.iter().collect();
+        assert_eq!(unique.len(), 10_000);
+
+        let a = g
+            .sample_distinct(50, &mut StdRng::seed_from_u64(3))
+            .unwrap();
+        let b = g
+            .sample_distinct(50, &mut StdRng::seed_from_u64(3))
+            .unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn distinct_uniform() {
+        // Every 2-subset of 4 values should be equally likely
+        let g = oneof!('a', 'b', 'c', 'd');
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        for _ in 0..60_000 {
+            let mut pair = g.sample_distinct(2, &mut rng).unwrap();
+            pair.sort();
+            *counts.entry(pair.concat()).or_default() += 1;
+        }
+        assert_eq!(counts.len(), 6);
+        let counts: Vec<_> = counts.into_values().collect();
+        // The critical value for 5 degrees of freedom at p = 0.001
+        assert!(chi_squared(&counts) < 20.515, "{counts:?}");
+    }
+
+    #[test]
+    #[cfg(feature = "bigint")]
+    fn reproducible_bigint() {
+        use rand::Rng;
+        let g = Generator::AlphaNumLower * 64;
+        assert!(g.try_len().is_err());
+
+        let values = g.generate_all_big();
+        let mut rng1 = StdRng::seed_from_u64(1234);
+        let mut rng2 = StdRng::seed_from_u64(1234);
+        assert_eq!(values.random_with(&mut rng1), values.random_with(&mut rng2));
+        assert_eq!(rng1.sample(&g), rng2.sample(&g));
+
+        let values = g.sample_distinct(1000, &mut rng1).unwrap();
+        assert_eq!(values.iter().collect::<HashSet<_>>().len(), 1000);
+        assert!(values.iter().all(|s| g.matches(s)));
+        assert_eq!(values, g.sample_distinct(1000, &mut rng2).unwrap());
+    }
+}
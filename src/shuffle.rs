@@ -0,0 +1,217 @@
+//! Enumeration of a [`Generator`]'s values in a pseudo-random order, without repeats.
+use crate::Generator;
+
+/// A seeded pseudo-random permutation of `0..n`.
+///
+/// This is a balanced Feistel network over the smallest domain of `2^(2h)` values holding `n`,
+/// made to fit `0..n` by cycle walking: indices that land outside the range are permuted again
+/// until they're inside it. Since the domain is less than four times `n`, this takes fewer
+/// than four passes on average. Each index is permuted independently, so nothing needs to be stored
+/// beyond the round keys.
+#[derive(Clone, Debug)]
+pub(crate) struct Permutation {
+    n: u128,
+
+    /// The number of bits in each half of a Feistel block
+    half_bits: u32,
+
+    keys: [u64; Self::ROUNDS],
+}
+
+impl Permutation {
+    const ROUNDS: usize = 6;
+
+    pub(crate) fn new(n: u128, seed: u64) -> Self {
+        // The smallest h such that 2^(2h) >= n, and at least 1 so that each half is nonempty
+        let bits = 128 - n.saturating_sub(1).leading_zeros();
+        let half_bits = bits.div_ceil(2).max(1);
+
+        let mut state = seed;
+        let keys = [(); Self::ROUNDS].map(|_| {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            mix(state)
+        });
+
+        Self { n, half_bits, keys }
+    }
+
+    /// The index at position `i` in the permuted order.
+    pub(crate) fn apply(&self, i: u128) -> u128 {
+        assert!(i < self.n);
+        let mut x = i;
+        loop {
+            x = self.encrypt(x);
+            if x < self.n {
+                return x;
+            }
+        }
+    }
+
+    /// One pass through the Feistel network, a permutation of `0..2^(2h)`.
+    fn encrypt(&self, x: u128) -> u128 {
+        let mask = u64::MAX >> (64 - self.half_bits);
+        let mut left = (x >> self.half_bits) as u64 & mask;
+        let mut right = x as u64 & mask;
+        for key in self.keys {
+            let f = mix(right ^ key) & mask;
+            (left, right) = (right, left ^ f);
+        }
+        (u128::from(left) << self.half_bits) | u128::from(right)
+    }
+}
+
+/// The SplitMix64 finalizer, used as the Feistel round function.
+fn mix(x: u64) -> u64 {
+    let x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    let x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Provides iterable access to the range of values represented by the [`Generator`] in a
+/// pseudo-random order determined by a seed, created by [`Generator::generate_shuffled`].
+#[derive(Clone, Debug)]
+pub struct ShuffledIter<'a> {
+    /// The generator to be used
+    c: &'a Generator,
+
+    /// The order in which to visit `c`'s values
+    permutation: Permutation,
+
+    /// The current position of the iterator. The first position is 0, the last is `n-1`.
+    i: u128,
+}
+
+impl<'a> ShuffledIter<'a> {
+    /// The position of the value that the next call to `next` will return, equal to the number of
+    /// values already returned.
+    pub fn position(&self) -> u128 {
+        self.i
+    }
+
+    /// Moves to `position` in the shuffled order, so that enumeration can resume there.
+    ///
+    /// Positions past the last value are clamped, leaving the iterator exhausted.
+    pub fn seek(&mut self, position: u128) {
+        self.i = position.min(self.permutation.n);
+    }
+}
+
+impl<'a> Iterator for ShuffledIter<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.i == self.permutation.n {
+            None
+        } else {
+            self.i += 1;
+            Some(self.c.generate_one(self.permutation.apply(self.i - 1)))
+        }
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.seek(self.i.saturating_add(n as u128));
+        self.next()
+    }
+}
+
+impl Generator {
+    /// Provides an iterator across all possible values for this `Generator` in a pseudo-random
+    /// order. Each value is produced exactly once, and the same `seed` always gives the same order.
+    ///
+    /// Values are shuffled by permuting their indices rather than by collecting them, so this
+    /// works for generators far too large to hold in memory. The iterator can
+    /// [`seek`](ShuffledIter::seek) to any position to resume an earlier enumeration.
+    ///
+    /// ```
+    /// use generator_combinator::Generator;
+    /// let g = Generator::AlphaLower * 2;
+    ///
+    /// let mut shuffled: Vec<_> = g.generate_shuffled(42).collect();
+    /// assert_ne!(shuffled, g.generate_all().collect::<Vec<_>>());
+    ///
+    /// // Resuming partway through continues the same order
+    /// let mut resumed = g.generate_shuffled(42);
+    /// resumed.seek(600);
+    /// assert_eq!(resumed.collect::<Vec<_>>(), shuffled[600..]);
+    ///
+    /// shuffled.sort();
+    /// assert_eq!(shuffled, g.generate_all().collect::<Vec<_>>());
+    /// ```
+    ///
+    /// Panics if the number of values exceeds `u128::MAX`.
+    pub fn generate_shuffled(&self, seed: u64) -> ShuffledIter<'_> {
+        ShuffledIter {
+            c: self,
+            permutation: Permutation::new(self.len(), seed),
+            i: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Permutation;
+    use crate::{oneof, Generator};
+
+    #[test]
+    fn bijection() {
+        for n in 0..=300 {
+            for seed in [0, 1, 0xDEAD_BEEF] {
+                let p = Permutation::new(n, seed);
+                let mut seen = vec![false; n as usize];
+                for i in 0..n {
+                    let j = p.apply(i);
+                    assert!(!seen[j as usize], "{j} repeated for n = {n}");
+                    seen[j as usize] = true;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn large() {
+        for n in [1_000_000_000_000, u128::MAX / 3, u128::MAX] {
+            let p = Permutation::new(n, 7);
+            let mut values: Vec<_> = (0..1000).map(|i| p.apply(i)).collect();
+            assert!(values.iter().all(|v| *v < n));
+            values.sort();
+            values.dedup();
+            assert_eq!(values.len(), 1000);
+        }
+    }
+
+    #[test]
+    fn seeded() {
+        let g = oneof!("foo", "bar", "baz") + Generator::Digit * 3;
+
+        let a: Vec<_> = g.generate_shuffled(1).collect();
+        let b: Vec<_> = g.generate_shuffled(1).collect();
+        let c: Vec<_> = g.generate_shuffled(2).collect();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+
+        let mut sorted = a.clone();
+        sorted.sort();
+        let mut expected: Vec<_> = g.generate_all().collect();
+        expected.sort();
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn resume() {
+        let g = Generator::HexLower * 3;
+        let all: Vec<_> = g.generate_shuffled(9).collect();
+
+        let mut iter = g.generate_shuffled(9);
+        assert_eq!(iter.nth(10).as_ref(), Some(&all[10]));
+        assert_eq!(iter.position(), 11);
+        assert_eq!(iter.next().as_ref(), Some(&all[11]));
+
+        iter.seek(4000);
+        assert_eq!(iter.collect::<Vec<_>>(), all[4000..]);
+
+        let mut iter = g.generate_shuffled(9);
+        iter.seek(u128::MAX);
+        assert_eq!(iter.next(), None);
+    }
+}
@@ -0,0 +1,834 @@
+//! Rendering a [`Generator`] as a regular expression.
+#[cfg(feature = "unicode")]
+use crate::char_class::Property;
+use crate::{CharClass, Generator, RegexError};
+use std::fmt::Write;
+
+/// A regex engine whose syntax [`Generator::regex_with`] should target.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dialect {
+    /// Perl-compatible regexes, as used by PCRE, Perl, and PHP.
+    ///
+    /// Python's `re` differs in ways that matter here: its `\d` matches any Unicode digit, and
+    /// before Python 3.14 it doesn't support the `\z` that ends an anchored regex.
+    #[default]
+    Pcre,
+
+    /// ECMAScript regexes. Unicode properties and characters outside the Basic Multilingual Plane
+    /// need the `u` flag.
+    JavaScript,
+
+    /// POSIX extended regular expressions, as used by `grep -E`. These have no `\d` and no
+    /// non-capturing groups.
+    PosixEre,
+
+    /// The Rust [`regex`](https://docs.rs/regex) crate, whose `\d` matches any Unicode digit and
+    /// so is avoided.
+    Rust,
+}
+
+/// Options for [`Generator::regex_with`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegexOptions {
+    /// The regex engine to target.
+    pub dialect: Dialect,
+
+    /// Whether to anchor the regex so that it only matches whole strings. A Perl-compatible regex
+    /// ends with `\z` rather than `$`, which would also match before a trailing newline.
+    pub anchored: bool,
+
+    /// Whether groups should be non-capturing, as `(?:...)`. POSIX EREs don't support these, so
+    /// their groups are always capturing.
+    pub non_capturing_groups: bool,
+
+    /// Whether a [`Transform`](Generator::Transform) may be rendered as the pattern it transforms,
+    /// and a [`DateTime`](Generator::DateTime) range as the combinations of its fields, giving a
+    /// regex that may not match exactly its values. Otherwise it's an error.
+    pub allow_inexact: bool,
+}
+
+/// How tightly a rendered regex binds, which decides whether it needs a group when combined with
+/// other regexes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    /// `a|b`, which needs a group inside a sequence or under a quantifier
+    Alternation,
+
+    /// `ab`, which needs a group under a quantifier
+    Concatenation,
+
+    /// `a?` or `a{2}`, which needs a group under another quantifier; otherwise `a??` would be a
+    /// lazy quantifier
+    Quantified,
+
+    /// A single character, class, or group
+    Atom,
+}
+
+/// A generator rendered as a regex.
+struct Rendered {
+    text: String,
+    precedence: Precedence,
+
+    /// Why `text` may not match exactly the generator's values, such as a transformation that
+    /// was skipped
+    inexact: Option<RegexError>,
+}
+
+impl Rendered {
+    fn empty() -> Self {
+        Self::new(String::new(), Precedence::Atom)
+    }
+
+    fn new(text: String, precedence: Precedence) -> Self {
+        Self {
+            text,
+            precedence,
+            inexact: None,
+        }
+    }
+}
+
+impl Generator {
+    /// Create a regular expression that represents the patterns generated.
+    ///
+    /// Characters with special meaning in a regex are escaped, and groups are added only where
+    /// needed. The regex matches exactly this generator's values, except that a
+    /// [`Transform`](Self::Transform) is rendered as the pattern it transforms (unless it's a
+    /// [`BuiltinTransform`](crate::BuiltinTransform) prefix, suffix, reversal, or case change), and a
+    /// [`DateTime`](Self::DateTime) range may also match impossible dates; see
+    /// [`try_regex`](Self::try_regex) to detect those cases, or [`regex_with`](Self::regex_with)
+    /// for other regex engines.
+    ///
+    /// ```
+    /// use generator_combinator::{oneof, Generator};
+    /// let g = oneof!("foo", "bar").optional() + Generator::from("(x)") * (2, 3);
+    /// assert_eq!(g.regex(), r"(foo|bar)?(\(x\)){2,3}");
+    /// ```
+    pub fn regex(&self) -> String {
+        let options = RegexOptions {
+            allow_inexact: true,
+            ..Default::default()
+        };
+        Renderer(&options).render(self).text
+    }
+
+    /// Like [`regex`](Self::regex), but returns [`RegexError::OpaqueTransform`] or
+    /// [`RegexError::DateTimeRange`] rather than a regex that may not match exactly this
+    /// generator's values.
+    ///
+    /// ```
+    /// use generator_combinator::{Generator, RegexError};
+    /// let g = Generator::Digit * 3;
+    /// assert_eq!(g.try_regex(), Ok(r"\d{3}".to_string()));
+    ///
+    /// let g = g.transform(|s: String| s.trim_start_matches('0').to_string());
+    /// assert_eq!(g.try_regex(), Err(RegexError::OpaqueTransform));
+    /// ```
+    pub fn try_regex(&self) -> Result<String, RegexError> {
+        self.regex_with(&RegexOptions::default())
+    }
+
+    /// Create a regular expression that represents the patterns generated, in the syntax and
+    /// style given by `options`.
+    ///
+    /// Returns [`RegexError::OpaqueTransform`] if this generator contains a
+    /// [`Transform`](Self::Transform), or [`RegexError::DateTimeRange`] if it contains a
+    /// [`DateTime`](Self::DateTime) range whose regex would match impossible dates, and
+    /// `options` doesn't allow inexact regexes.
+    ///
+    /// ```
+    /// use generator_combinator::{oneof, Dialect, Generator, RegexOptions};
+    /// let g = oneof!("foo", "bar") + Generator::AlphaNumUpper * (2, 3);
+    ///
+    /// let options = RegexOptions {
+    ///     dialect: Dialect::Rust,
+    ///     anchored: true,
+    ///     non_capturing_groups: true,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(g.regex_with(&options).unwrap(), "^(?:foo|bar)[A-Z0-9]{2,3}$");
+    ///
+    /// let options = RegexOptions {
+    ///     dialect: Dialect::PosixEre,
+    ///     ..options
+    /// };
+    /// assert_eq!(g.regex_with(&options).unwrap(), "^(foo|bar)[A-Z0-9]{2,3}$");
+    /// ```
+    pub fn regex_with(&self, options: &RegexOptions) -> Result<String, RegexError> {
+        let renderer = Renderer(options);
+        let rendered = renderer.render(self);
+        if let Some(e) = rendered.inexact.clone().filter(|_| !options.allow_inexact) {
+            return Err(e);
+        }
+        if !options.anchored {
+            return Ok(rendered.text);
+        }
+
+        let (start, end) = match options.dialect {
+            Dialect::Pcre => ("\\A", "\\z"),
+            Dialect::JavaScript | Dialect::PosixEre | Dialect::Rust => ("^", "$"),
+        };
+        Ok(format!(
+            "{start}{}{end}",
+            renderer.at_least(&rendered, Precedence::Concatenation)
+        ))
+    }
+}
+
+/// Renders generators as regexes according to the options it holds.
+struct Renderer<'a>(&'a RegexOptions);
+
+impl<'a> Renderer<'a> {
+    /// The text of `r` as a group.
+    fn group(&self, r: &Rendered) -> String {
+        if self.0.non_capturing_groups && self.0.dialect != Dialect::PosixEre {
+            format!("(?:{})", r.text)
+        } else {
+            format!("({})", r.text)
+        }
+    }
+
+    /// The text of `r`, grouped if it binds more loosely than `precedence`.
+    fn at_least(&self, r: &Rendered, precedence: Precedence) -> String {
+        if r.precedence < precedence {
+            self.group(r)
+        } else {
+            r.text.clone()
+        }
+    }
+
+    /// Applies the quantifier `q` to `r`. Since the empty regex only matches the empty string,
+    /// it's left as-is.
+    fn quantify(&self, r: Rendered, q: &str) -> Rendered {
+        if r.text.is_empty() {
+            return r;
+        }
+        Rendered {
+            text: self.at_least(&r, Precedence::Atom) + q,
+            precedence: Precedence::Quantified,
+            inexact: r.inexact,
+        }
+    }
+
+    /// The digits `0-9`, within a bracketed class.
+    fn digits(&self) -> &'static str {
+        match self.0.dialect {
+            Dialect::Pcre | Dialect::JavaScript => "\\d",
+            Dialect::PosixEre | Dialect::Rust => "0-9",
+        }
+    }
+
+    /// A regex that matches nothing, for generators with no values.
+    fn never(&self) -> Rendered {
+        match self.0.dialect {
+            // Bracket expressions have no escapes, but nothing can precede the start of a line
+            Dialect::PosixEre => Rendered::new("a^".into(), Precedence::Concatenation),
+            _ => Rendered::new("[^\\s\\S]".into(), Precedence::Atom),
+        }
+    }
+
+    fn render(&self, g: &Generator) -> Rendered {
+        use Generator::*;
+        use Precedence::*;
+
+        // The parts of a generator with no values may still render as something that matches
+        if g.try_len() == Ok(0) {
+            return self.never();
+        }
+
+        match g {
+            AlphaLower => Rendered::new("[a-z]".into(), Atom),
+            AlphaUpper => Rendered::new("[A-Z]".into(), Atom),
+            Digit => match self.0.dialect {
+                Dialect::Pcre | Dialect::JavaScript => Rendered::new("\\d".into(), Atom),
+                Dialect::PosixEre | Dialect::Rust => Rendered::new("[0-9]".into(), Atom),
+            },
+            AlphaNumUpper => Rendered::new(format!("[A-Z{}]", self.digits()), Atom),
+            AlphaNumLower => Rendered::new(format!("[a-z{}]", self.digits()), Atom),
+            HexUpper => Rendered::new(format!("[{}A-F]", self.digits()), Atom),
+            HexLower => Rendered::new(format!("[{}a-f]", self.digits()), Atom),
+            Base64 => Rendered::new(format!("[A-Za-z{}+/]", self.digits()), Atom),
+            Base64Url => Rendered::new(format!("[A-Za-z{}_-]", self.digits()), Atom),
+            Base32 => Rendered::new("[A-Z2-7]".into(), Atom),
+            Base58 => Rendered::new("[1-9A-HJ-NP-Za-km-z]".into(), Atom),
+            PrintableAscii => Rendered::new("[ -~]".into(), Atom),
+            Punctuation => match self.0.dialect {
+                Dialect::PosixEre => Rendered::new("[[:punct:]]".into(), Atom),
+                _ => Rendered::new("[!-/:-@\\[-`{-~]".into(), Atom),
+            },
+            Whitespace => match self.0.dialect {
+                Dialect::PosixEre => Rendered::new("[[:space:]]".into(), Atom),
+                _ => Rendered::new("[\\t\\n\\x0B\\f\\r ]".into(), Atom),
+            },
+            CharClass(class) => self.render_class(class),
+            IntRange(range) => self.render(&range.to_generator()),
+            DateTime(range) => {
+                // Every combination of the fields' values is exactly the range's values only if
+                // there are just as many of them
+                let fields = range.to_generator();
+                let mut rendered = self.render(&fields);
+                if fields.try_len() != Ok(range.span() + 1) {
+                    rendered.inexact = Some(RegexError::DateTimeRange);
+                }
+                rendered
+            }
+            Char(c) => {
+                let mut text = String::new();
+                self.push_escaped(&mut text, *c);
+                Rendered::new(text, Atom)
+            }
+            Str(s) => {
+                let mut text = String::new();
+                s.chars().for_each(|c| self.push_escaped(&mut text, c));
+                let precedence = if s.chars().nth(1).is_some() {
+                    Concatenation
+                } else {
+                    Atom
+                };
+                Rendered::new(text, precedence)
+            }
+            OneOf { v, is_optional } => {
+                let branches: Vec<_> = v
+                    .iter()
+                    .filter(|a| a.try_len() != Ok(0))
+                    .map(|a| self.render(a))
+                    .collect();
+                let inexact = branches.iter().find_map(|b| b.inexact.clone());
+
+                // An empty branch is the same as making the others optional
+                let is_optional = *is_optional || branches.iter().any(|b| b.text.is_empty());
+                let mut branches: Vec<_> = branches
+                    .into_iter()
+                    .filter(|b| !b.text.is_empty())
+                    .collect();
+
+                let mut rendered = match branches.len() {
+                    0 => Rendered::empty(),
+                    1 => branches.remove(0),
+                    _ => {
+                        let texts: Vec<_> = branches.iter().map(|b| b.text.as_str()).collect();
+                        Rendered::new(texts.join("|"), Alternation)
+                    }
+                };
+                if is_optional {
+                    rendered = self.quantify(rendered, "?");
+                }
+                rendered.inexact = inexact;
+                rendered
+            }
+            RepeatedN(a, n) => match n {
+                0 => Rendered::empty(),
+                1 => self.render(a),
+                n => self.quantify(self.render(a), &format!("{{{n}}}")),
+            },
+            RepeatedMN(a, m, n) => match (m, n) {
+                (_, 0) => Rendered::empty(),
+                (0, 1) => self.quantify(self.render(a), "?"),
+                (1, 1) => self.render(a),
+                (m, n) if m == n => self.quantify(self.render(a), &format!("{{{n}}}")),
+                (m, n) => self.quantify(self.render(a), &format!("{{{m},{n}}}")),
+            },
+            Sequence(v) => {
+                let parts: Vec<_> = v
+                    .iter()
+                    .map(|a| self.render(a))
+                    .filter(|p| !p.text.is_empty() || p.inexact.is_some())
+                    .collect();
+                let inexact = parts.iter().find_map(|p| p.inexact.clone());
+                let mut rendered = match parts.len() {
+                    0 => Rendered::empty(),
+                    1 => parts.into_iter().next().unwrap(),
+                    _ => {
+                        let text = parts
+                            .iter()
+                            .map(|p| self.at_least(p, Concatenation))
+                            .collect();
+                        Rendered::new(text, Concatenation)
+                    }
+                };
+                rendered.inexact = inexact;
+                rendered
+            }
+            Transform {
+                inner,
+                transform_fn,
+            } => match transform_fn.builtin().and_then(|t| t.map_generator(inner)) {
+                Some(transformed) => self.render(&transformed),
+                None => Rendered {
+                    inexact: Some(RegexError::OpaqueTransform),
+                    ..self.render(inner)
+                },
+            },
+            Filter(filter) => Rendered {
+                inexact: Some(RegexError::Filter),
+                ..self.render(filter.inner())
+            },
+            Automaton(automaton) => self.render(&automaton.to_generator()),
+            Empty => Rendered::empty(),
+        }
+    }
+
+    /// Renders `class` as a bracketed class, or as a single character if it has only one.
+    fn render_class(&self, class: &CharClass) -> Rendered {
+        let mut text = String::new();
+        match class.len() {
+            0 => return self.never(),
+            1 => {
+                let c = class.get(0).expect("the class has a character");
+                self.push_escaped(&mut text, c);
+                return Rendered::new(text, Precedence::Atom);
+            }
+            _ => {}
+        }
+
+        #[cfg(feature = "unicode")]
+        if let Some(property) = class.property() {
+            if let Some(text) = self.property(property) {
+                return Rendered::new(text, Precedence::Atom);
+            }
+        }
+
+        text.push('[');
+        if self.0.dialect == Dialect::PosixEre {
+            // Bracket expressions have no escapes, so `]` must come first, `^` anywhere but
+            // first, and `-` last
+            let special: CharClass = "]^\\-".parse().expect("valid class");
+            if class.contains(']') {
+                text.push(']');
+            }
+            self.push_ranges(&mut text, &special.negate(class));
+            if class.contains('^') {
+                if text.len() == 1 {
+                    text.push('-');
+                }
+                text.push('^');
+            }
+            if class.contains('-') && !text.ends_with("-^") {
+                text.push('-');
+            }
+        } else {
+            self.push_ranges(&mut text, class);
+        }
+        text.push(']');
+        Rendered::new(text, Precedence::Atom)
+    }
+
+    /// Matches the characters with `property`, or `None` if the dialect has no such syntax.
+    #[cfg(feature = "unicode")]
+    fn property(&self, property: &Property) -> Option<String> {
+        let text = match (self.0.dialect, property) {
+            (Dialect::PosixEre, _) => return None,
+            (Dialect::JavaScript, Property::Script(name)) => format!("\\p{{Script={name}}}"),
+            (
+                _,
+                Property::GeneralCategory(name) | Property::Script(name) | Property::Binary(name),
+            ) => {
+                format!("\\p{{{name}}}")
+            }
+        };
+        Some(text)
+    }
+
+    /// Appends the ranges of `class` to `text`, as they would appear within a bracketed class.
+    fn push_ranges(&self, text: &mut String, class: &CharClass) {
+        for r in class.ranges() {
+            let (start, end) = (*r.start(), *r.end());
+            self.push_class_escaped(text, start);
+            if end as u32 > start as u32 + 1 {
+                text.push('-');
+            }
+            if end != start {
+                self.push_class_escaped(text, end);
+            }
+        }
+    }
+
+    /// Appends `c` to `text`, escaped if it has special meaning in a regex.
+    fn push_escaped(&self, text: &mut String, c: char) {
+        match c {
+            // These are ordinary characters in a POSIX ERE, where escaping them is undefined
+            ']' | '}' if self.0.dialect == Dialect::PosixEre => text.push(c),
+            '\\' | '.' | '+' | '*' | '?' | '(' | ')' | '|' | '[' | ']' | '{' | '}' | '^' | '$' => {
+                text.push('\\');
+                text.push(c);
+            }
+            c => self.push_literal(text, c),
+        }
+    }
+
+    /// Appends `c` to `text`, escaped if it has special meaning within a bracketed class.
+    fn push_class_escaped(&self, text: &mut String, c: char) {
+        match c {
+            // POSIX bracket expressions are handled by `render_class` instead
+            '\\' | '[' | ']' | '^' | '-' if self.0.dialect != Dialect::PosixEre => {
+                text.push('\\');
+                text.push(c);
+            }
+            c => self.push_literal(text, c),
+        }
+    }
+
+    /// Appends `c` to `text`, escaped if it's a control character.
+    fn push_literal(&self, text: &mut String, c: char) {
+        match c {
+            // POSIX EREs have no escapes for control characters, but match them literally
+            c if self.0.dialect == Dialect::PosixEre => text.push(c),
+            '\n' => text.push_str("\\n"),
+            '\r' => text.push_str("\\r"),
+            '\t' => text.push_str("\\t"),
+            // Control characters are all below U+00A0, so two hex digits are enough
+            c if c.is_control() => write!(text, "\\x{:02X}", c as u32).unwrap(),
+            c => text.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{gen, oneof, Dialect, Generator, RegexError, RegexOptions};
+    use regex::Regex;
+
+    /// Checks that `g`'s regex compiles and matches values sampled evenly across its range.
+    fn assert_regex_matches(g: &Generator) -> Regex {
+        let pattern = g.try_regex().unwrap();
+        let re = Regex::new(&format!("^(?:{pattern})$")).unwrap();
+
+        let n = g.len();
+        let step = (n / 1000).max(1);
+        for i in (0..n).step_by(step as usize).chain([n - 1]) {
+            let value = g.generate_one(i);
+            assert!(re.is_match(&value), "{pattern} doesn't match {value:?}");
+        }
+        re
+    }
+
+    #[test]
+    fn escaping() {
+        let special = "a.b*c+d?(e)[f]{g}|h^i$\\j\n\t\x01";
+        let g = Generator::from(special);
+        assert_eq!(
+            g.regex(),
+            "a\\.b\\*c\\+d\\?\\(e\\)\\[f\\]\\{g\\}\\|h\\^i\\$\\\\j\\n\\t\\x01"
+        );
+        let re = assert_regex_matches(&g);
+        assert!(!re.is_match("a.b*c+d?(e)[f]{g}|h^i$\\j"));
+
+        for c in special.chars() {
+            assert_regex_matches(&Generator::Char(c));
+        }
+    }
+
+    #[test]
+    fn grouping() {
+        assert_eq!((gen!("hi") * (4, 5)).regex(), "(hi){4,5}");
+        assert_eq!((gen!('h') * (4, 5)).regex(), "h{4,5}");
+        assert_eq!((gen!("hi") * 3).regex(), "(hi){3}");
+        assert_eq!((gen!('a') * (1, 2) * 3).regex(), "(a{1,2}){3}");
+        assert_eq!(gen!('a').optional().optional().regex(), "a?");
+        assert_eq!((gen!('a').optional() * 2).regex(), "(a?){2}");
+        assert_eq!((gen!('a') * (0, 3)).regex(), "(a{1,3})?");
+        assert_eq!((gen!('a') * (2, 2)).regex(), "a{2}");
+        assert_eq!(
+            (oneof!("a", "b") + oneof!("c", "d") * 2).regex(),
+            "(a|b)(c|d){2}"
+        );
+        assert_eq!((gen!("ab") + gen!("cd")).regex(), "abcd");
+        assert_eq!((gen!("ab") | gen!("cd")).regex(), "ab|cd");
+        assert_eq!(
+            (oneof!("ab", "cd") | (gen!('x') + oneof!('y', 'z'))).regex(),
+            "ab|cd|x(y|z)"
+        );
+
+        for g in [
+            gen!("hi") * (4, 5),
+            gen!('a') * (1, 2) * 3,
+            (gen!('a').optional() * 2) + oneof!("bc", "d").optional(),
+            oneof!("N", "E", "S", "W").optional()
+                + ' '
+                + Generator::Digit * (1, 2)
+                + (gen!('-') + oneof!('A', 'B')).optional() * 2,
+        ] {
+            let re = assert_regex_matches(&g);
+            // Values from the generator's own ranges are matched, and no others
+            assert_eq!(
+                g.generate_all().filter(|s| re.is_match(s)).count() as u128,
+                g.len()
+            );
+        }
+        let re = assert_regex_matches(&(gen!("hi") * (4, 5)));
+        assert!(!re.is_match("hiiii"));
+    }
+
+    #[test]
+    fn builtin_classes() {
+        use Generator::*;
+        assert_eq!(Base64.regex(), r"[A-Za-z\d+/]");
+        assert_eq!(Base58.regex(), "[1-9A-HJ-NP-Za-km-z]");
+        let posix = RegexOptions {
+            dialect: Dialect::PosixEre,
+            ..Default::default()
+        };
+        assert_eq!(Punctuation.regex_with(&posix).unwrap(), "[[:punct:]]");
+
+        for g in [
+            AlphaLower,
+            AlphaUpper,
+            Digit,
+            AlphaNumLower,
+            AlphaNumUpper,
+            HexUpper,
+            HexLower,
+            Base64,
+            Base64Url,
+            Base32,
+            Base58,
+            PrintableAscii,
+            Punctuation,
+            Whitespace,
+        ] {
+            // The regex crate understands the syntax of each dialect used for these classes
+            for dialect in [Dialect::Pcre, Dialect::PosixEre, Dialect::Rust] {
+                let options = RegexOptions {
+                    dialect,
+                    anchored: true,
+                    ..Default::default()
+                };
+                let re = Regex::new(&g.regex_with(&options).unwrap()).unwrap();
+                let matching = (0..128u8)
+                    .map(|b| char::from(b).to_string())
+                    .filter(|s| re.is_match(s));
+                assert!(matching.eq(g.generate_all().collect::<std::collections::BTreeSet<_>>()));
+            }
+        }
+    }
+
+    #[test]
+    fn classes() {
+        assert_eq!(Generator::class("a-fxz").regex(), "[a-fxz]");
+        assert_eq!(Generator::class("abcd").regex(), "[a-d]");
+        assert_eq!(Generator::class("ab").regex(), "[ab]");
+        assert_eq!(Generator::class("x").regex(), "x");
+        assert_eq!(Generator::class(".").regex(), "\\.");
+        assert_eq!((Generator::class("ab") * 2).regex(), "[ab]{2}");
+        assert_eq!(Generator::class(r"\]\\^\-").regex(), r"[\-\\-\^]");
+        assert_eq!(Generator::class("\t\n").regex(), r"[\t\n]");
+
+        let posix = RegexOptions {
+            dialect: Dialect::PosixEre,
+            ..Default::default()
+        };
+        for (spec, expected) in [
+            (r"\]a-c\-", "[]a-c-]"),
+            (r"^a", "[a^]"),
+            (r"^\-", "[-^]"),
+            (r"\]^\-", "[]^-]"),
+            (r"\\a", r"[\a]"),
+            (r"\\-a", r"[]\_-a^]"),
+        ] {
+            assert_eq!(Generator::class(spec).regex_with(&posix).unwrap(), expected);
+        }
+
+        for spec in [
+            "a-fxz",
+            r"\]\\^\-",
+            r"!-/",
+            "\t\n\x01",
+            "а-яё",
+            "\u{1F600}-\u{1F64F}",
+        ] {
+            let g = Generator::class(spec);
+            let re = assert_regex_matches(&g);
+            assert_eq!(
+                g.generate_all().filter(|s| re.is_match(s)).count() as u128,
+                g.len()
+            );
+            assert!(!re.is_match("y"));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn unicode() {
+        let g = Generator::script("Greek") + Generator::general_category("Mn").optional();
+        assert_eq!(g.regex(), r"\p{Greek}\p{Mn}?");
+        let js = RegexOptions {
+            dialect: Dialect::JavaScript,
+            ..Default::default()
+        };
+        assert_eq!(g.regex_with(&js).unwrap(), r"\p{Script=Greek}\p{Mn}?");
+
+        // POSIX EREs have no properties, so the characters are listed
+        let posix = RegexOptions {
+            dialect: Dialect::PosixEre,
+            ..Default::default()
+        };
+        let cyrillic = Generator::script("Cyrillic");
+        let listed = cyrillic.regex_with(&posix).unwrap();
+        assert!(listed.starts_with("[Ѐ-҄"));
+
+        // A class changed from a property lists its characters too
+        let Generator::CharClass(class) = &cyrillic else {
+            unreachable!()
+        };
+        let g = Generator::from(class.difference(&"а-я".parse().unwrap()));
+        assert!(g.regex().starts_with("[Ѐ-Я"));
+
+        assert!(!assert_regex_matches(&g).is_match("а"));
+        assert!(!assert_regex_matches(&Generator::general_category("Lu")).is_match("а"));
+        assert!(assert_regex_matches(&cyrillic).is_match("а"));
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(Generator::Empty.regex(), "");
+        assert_eq!(Generator::Empty.optional().regex(), "");
+        assert_eq!((Generator::Empty * (2, 3)).regex(), "");
+        assert_eq!((gen!("ab") | Generator::Empty).regex(), "(ab)?");
+        assert_eq!((oneof!("ab", "c") | Generator::Empty).regex(), "(ab|c)?");
+        assert_eq!(
+            (gen!('a') + Generator::Empty.optional() + 'b').regex(),
+            "ab"
+        );
+        assert_eq!((gen!('a') * (0, 0)).regex(), "");
+
+        let g = gen!('x') + (gen!("ab") | Generator::Empty);
+        let re = assert_regex_matches(&g);
+        assert!(re.is_match("x"));
+        assert!(!re.is_match("xa"));
+    }
+
+    #[test]
+    fn no_values() {
+        let none = Generator::OneOf {
+            v: vec![],
+            is_optional: false,
+        };
+        assert_eq!(none.try_regex(), Ok(r"[^\s\S]".into()));
+        assert_eq!(
+            Generator::from(crate::CharClass::default()).regex(),
+            r"[^\s\S]"
+        );
+        assert_eq!(none.clone().optional().regex(), "");
+        assert_eq!((gen!('a') | none.clone()).regex(), "a");
+        assert_eq!((none.clone() * (0, 2)).regex(), "");
+
+        let g = gen!("x") + none.clone();
+        assert!(g.is_empty());
+        assert_eq!(g.regex(), r"[^\s\S]");
+        let g = gen!("x") + (gen!('y') | none.clone()) * 2 + (none.clone() + 'z').optional();
+        assert_eq!(g.regex(), "xy{2}");
+        let re = assert_regex_matches(&g);
+        assert!(!re.is_match("xyyz"));
+
+        let re = Regex::new(&format!("^(?:x|{})$", none.regex())).unwrap();
+        assert!(re.is_match("x"));
+        assert!(!re.is_match(""));
+
+        let posix = RegexOptions {
+            dialect: Dialect::PosixEre,
+            anchored: true,
+            ..Default::default()
+        };
+        assert_eq!(none.regex_with(&posix), Ok("^a^$".into()));
+        assert_eq!((none.clone() * (0, 1)).regex_with(&posix), Ok("^$".into()));
+    }
+
+    #[test]
+    fn transform() {
+        let g = gen!("ab") + (Generator::Digit * 2).transform(|s: String| s.replace('0', ""));
+        assert_eq!(g.regex(), "ab\\d{2}");
+        assert_eq!(g.try_regex(), Err(RegexError::OpaqueTransform));
+
+        // Even a transformation of an empty generator can produce something
+        let g = gen!('a') + Generator::Empty.transform(|_: String| "b".into());
+        assert_eq!(g.try_regex(), Err(RegexError::OpaqueTransform));
+        let g = oneof!("a", "b").optional() + Generator::Empty.transform(|_: String| "b".into());
+        assert_eq!(g.try_regex(), Err(RegexError::OpaqueTransform));
+    }
+
+    #[test]
+    fn dialects() {
+        let g = oneof!("ab", "c").optional() + Generator::HexLower * (1, 2) + Generator::Digit;
+        let regex_for = |dialect, non_capturing_groups| {
+            let options = RegexOptions {
+                dialect,
+                anchored: true,
+                non_capturing_groups,
+                ..Default::default()
+            };
+            g.regex_with(&options).unwrap()
+        };
+
+        assert_eq!(
+            regex_for(Dialect::Pcre, false),
+            r"\A(ab|c)?[\da-f]{1,2}\d\z"
+        );
+        assert_eq!(
+            regex_for(Dialect::JavaScript, true),
+            r"^(?:ab|c)?[\da-f]{1,2}\d$"
+        );
+        assert_eq!(
+            regex_for(Dialect::PosixEre, true),
+            "^(ab|c)?[0-9a-f]{1,2}[0-9]$"
+        );
+        assert_eq!(
+            regex_for(Dialect::Rust, true),
+            "^(?:ab|c)?[0-9a-f]{1,2}[0-9]$"
+        );
+
+        // Anchoring groups a top-level alternation so the anchors apply to every branch
+        let options = RegexOptions {
+            anchored: true,
+            non_capturing_groups: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            oneof!("ab", "c").regex_with(&options).unwrap(),
+            r"\A(?:ab|c)\z"
+        );
+
+        // The Rust dialect compiles with the regex crate, and no `\d` matches non-ASCII digits
+        let re = Regex::new(&regex_for(Dialect::Rust, true)).unwrap();
+        assert!(g.generate_all().all(|s| re.is_match(&s)));
+        assert!(!re.is_match("ab0٣"));
+        assert!(!re.is_match("ab00\n"));
+
+        let g = Generator::from('\x01').transform(|s: String| s.repeat(2));
+        assert_eq!(
+            g.regex_with(&RegexOptions::default()),
+            Err(RegexError::OpaqueTransform)
+        );
+        let options = RegexOptions {
+            dialect: Dialect::PosixEre,
+            allow_inexact: true,
+            ..Default::default()
+        };
+        assert_eq!(g.regex_with(&options), Ok("\x01".into()));
+
+        // Only characters with special meaning are escaped in a POSIX ERE
+        let g = gen!("[a]{2}") + '}';
+        assert_eq!(g.regex_with(&options), Ok(r"\[a]\{2}}".into()));
+        assert_eq!(g.regex(), r"\[a\]\{2\}\}");
+    }
+
+    #[test]
+    #[cfg(feature = "from_regex")]
+    fn round_trip() {
+        for g in [
+            gen!("a.b") * (1, 2) + oneof!("(x)", "y|z").optional(),
+            oneof!("foo", "bar", "baz") + Generator::Digit * 2 + Generator::HexLower,
+            gen!("hi") * 2 + (gen!("[?]") | Generator::Empty),
+        ] {
+            // Values may be in a different order, e.g. when an empty branch becomes `?`
+            let mut parsed: Vec<_> = Generator::from_regex(&g.regex())
+                .unwrap()
+                .generate_all()
+                .collect();
+            let mut expected: Vec<_> = g.generate_all().collect();
+            parsed.sort();
+            expected.sort();
+            assert_eq!(parsed, expected);
+        }
+    }
+}
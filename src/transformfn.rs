@@ -1,16 +1,669 @@
-#[derive(Clone, Eq)]
-pub struct TransformFn(pub(crate) Box<fn(String) -> String>);
+use crate::index::Index;
+use crate::{CharClass, Generator};
+#[cfg(feature = "bigint")]
+use num_bigint::BigUint;
+use std::sync::Arc;
+
+/// A user-defined transformation applied by [`Generator::Transform`](crate::Generator::Transform).
+///
+/// A closure can't be inspected, so it carries an optional name that stands in for what it does.
+/// Named closures are equal when their names are, as long as both are plain or both are
+/// [contextual](Self::contextual); an unnamed closure is only equal to itself (and its clones), so
+/// two generators that differ only in an unnamed closure compare unequal. A [`BuiltinTransform`]
+/// is only equal to the same built-in transform with the same name, if any.
+///
+/// A closure made with [`contextual`](Self::contextual) also receives a [`TransformContext`].
+#[derive(Clone)]
+pub struct TransformFn {
+    f: Function,
+    name: Option<String>,
+}
+
+type ContextualFn = dyn Fn(String, &TransformContext) -> String + Send + Sync;
+
+#[derive(Clone)]
+enum Function {
+    Closure(Arc<dyn Fn(String) -> String + Send + Sync>),
+    Contextual(Arc<ContextualFn>),
+    Builtin(BuiltinTransform),
+}
+
+impl TransformFn {
+    /// Wraps a function or closure as an unnamed transform.
+    pub fn new(f: impl Fn(String) -> String + Send + Sync + 'static) -> Self {
+        Self {
+            f: Function::Closure(Arc::new(f)),
+            name: None,
+        }
+    }
+
+    /// Wraps a function or closure that also takes the [`TransformContext`] of each value as an
+    /// unnamed transform.
+    pub fn contextual(
+        f: impl Fn(String, &TransformContext) -> String + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            f: Function::Contextual(Arc::new(f)),
+            name: None,
+        }
+    }
+
+    /// Wraps a function or closure as a transform identified by `name`.
+    pub fn named(
+        name: impl Into<String>,
+        f: impl Fn(String) -> String + Send + Sync + 'static,
+    ) -> Self {
+        Self::new(f).with_name(name)
+    }
+
+    /// Names this transform, replacing any previous name.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// The name of this transform, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The built-in transform this applies, if it isn't a closure.
+    pub fn builtin(&self) -> Option<&BuiltinTransform> {
+        match &self.f {
+            Function::Builtin(t) => Some(t),
+            Function::Closure(_) | Function::Contextual(_) => None,
+        }
+    }
+
+    /// Whether this transform needs the [`TransformContext`] of each value.
+    pub fn is_contextual(&self) -> bool {
+        matches!(self.f, Function::Contextual(_))
+    }
+
+    /// Applies the transform to `s`.
+    ///
+    /// Panics if the transform [is contextual](Self::is_contextual), since there's no context to
+    /// give it.
+    pub fn apply(&self, s: String) -> String {
+        match &self.f {
+            Function::Closure(f) => f(s),
+            Function::Contextual(_) => panic!("a contextual transform needs a TransformContext"),
+            Function::Builtin(t) => t.apply(s),
+        }
+    }
+
+    /// Applies the transform to `s`, the value described by `context`.
+    pub(crate) fn apply_in(&self, s: String, context: &TransformContext) -> String {
+        match &self.f {
+            Function::Contextual(f) => f(s, context),
+            _ => self.apply(s),
+        }
+    }
+}
+
+impl<F: Fn(String) -> String + Send + Sync + 'static> From<F> for TransformFn {
+    fn from(f: F) -> Self {
+        Self::new(f)
+    }
+}
+
+impl From<Arc<dyn Fn(String) -> String + Send + Sync>> for TransformFn {
+    fn from(f: Arc<dyn Fn(String) -> String + Send + Sync>) -> Self {
+        Self {
+            f: Function::Closure(f),
+            name: None,
+        }
+    }
+}
+
+impl From<BuiltinTransform> for TransformFn {
+    fn from(t: BuiltinTransform) -> Self {
+        Self {
+            f: Function::Builtin(t),
+            name: None,
+        }
+    }
+}
 
 impl std::fmt::Debug for TransformFn {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "<TransformFn>")
+        match (&self.name, &self.f) {
+            (Some(name), _) => write!(f, "TransformFn({name:?})"),
+            (None, Function::Builtin(t)) => write!(f, "TransformFn({t:?})"),
+            (None, Function::Closure(_) | Function::Contextual(_)) => {
+                write!(f, "TransformFn(<unnamed>)")
+            }
+        }
     }
 }
 
-/// **Huge caveat**: define _all_ transforms to be equal since we can't inspect what they're going to do.
-/// This allows us to continue using `PartialEq` with [Generator]
 impl PartialEq for TransformFn {
-    fn eq(&self, _other: &Self) -> bool {
-        true
+    fn eq(&self, other: &Self) -> bool {
+        let named = match (&self.name, &other.name) {
+            (Some(a), Some(b)) if a == b => true,
+            (None, None) => false,
+            _ => return false,
+        };
+        match (&self.f, &other.f) {
+            (Function::Closure(a), Function::Closure(b)) => named || Arc::ptr_eq(a, b),
+            (Function::Contextual(a), Function::Contextual(b)) => named || Arc::ptr_eq(a, b),
+            (Function::Builtin(a), Function::Builtin(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for TransformFn {}
+
+/// The serialized form of a [`TransformFn`], which must be built-in
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SerializedTransform {
+    builtin: BuiltinTransform,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+/// Only a [`BuiltinTransform`] can be serialized, with its name if it has one; serializing a
+/// closure is an error.
+#[cfg(feature = "serde")]
+impl serde::Serialize for TransformFn {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Some(builtin) = self.builtin() else {
+            return Err(serde::ser::Error::custom(
+                "only built-in transforms can be serialized, not closures",
+            ));
+        };
+        let t = SerializedTransform {
+            builtin: builtin.clone(),
+            name: self.name.clone(),
+        };
+        serde::Serialize::serialize(&t, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TransformFn {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let t: SerializedTransform = serde::Deserialize::deserialize(deserializer)?;
+        Ok(Self {
+            f: Function::Builtin(t.builtin),
+            name: t.name,
+        })
+    }
+}
+
+/// What a [contextual](TransformFn::contextual) transform knows about the value it transforms,
+/// besides the value itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransformContext {
+    index: Option<u128>,
+    #[cfg(feature = "bigint")]
+    index_big: BigUint,
+    parts: Vec<String>,
+}
+
+impl TransformContext {
+    pub(crate) fn new<I: Index>(index: &I, parts: Vec<String>) -> Self {
+        Self {
+            index: index.to_u128(),
+            #[cfg(feature = "bigint")]
+            index_big: index.to_big(),
+            parts,
+        }
+    }
+
+    /// The index of the value among those of the transformed generator, as given to its
+    /// [`generate_one`](Generator::generate_one).
+    ///
+    /// Panics if the index exceeds `u128::MAX`, which only happens for generators with the
+    /// `bigint` feature; see [`index_big`](Self::index_big).
+    pub fn index(&self) -> u128 {
+        self.index.expect("the index exceeds u128::MAX")
+    }
+
+    /// The index of the value among those of the transformed generator, as given to its
+    /// [`generate_one_big`](Generator::generate_one_big).
+    #[cfg(feature = "bigint")]
+    pub fn index_big(&self) -> &BigUint {
+        &self.index_big
+    }
+
+    /// The parts of the value, as [`visit_one`](Generator::visit_one) on the transformed
+    /// generator would pass them to its callback.
+    pub fn parts(&self) -> &[String] {
+        &self.parts
+    }
+}
+
+/// A standard transformation. Unlike a closure, its effect is known: it's shown by `Debug`,
+/// serialized with the `serde` feature, rendered exactly by [`Generator::regex`] where the
+/// transformed values have a regular structure, and inverted by [`Generator::index_of`] where
+/// each value has finitely many sources.
+///
+/// A [`Generator`] whose transforms are all built-in can be serialized too, while serializing
+/// one with a closure is an error.
+///
+/// ```
+/// use generator_combinator::{BuiltinTransform, Generator};
+/// let g = (Generator::AlphaLower * 2).transform(BuiltinTransform::Uppercase);
+/// assert_eq!(g.generate_one(27), "BB");
+/// assert_eq!(g.try_regex().unwrap(), "[A-Z]{2}");
+///
+/// let g = Generator::int_range(1..=99).transform(BuiltinTransform::PadStart {
+///     width: 4,
+///     fill: '0',
+/// });
+/// assert_eq!(g.generate_one(41), "0042");
+/// assert_eq!(g.index_of("0042"), Some(41));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BuiltinTransform {
+    /// Removes leading `0`s, keeping one if the value is all zeros: `007` becomes `7`, and `000`
+    /// becomes `0`.
+    TrimLeadingZeros,
+
+    /// Converts to uppercase, as [`str::to_uppercase`] does.
+    Uppercase,
+
+    /// Converts to lowercase, as [`str::to_lowercase`] does.
+    Lowercase,
+
+    /// Uppercases the first character of each whitespace-separated word and lowercases the rest.
+    TitleCase,
+
+    /// Reverses the order of the characters.
+    Reverse,
+
+    /// Adds `fill` to the start of values shorter than `width` characters.
+    PadStart { width: usize, fill: char },
+
+    /// Adds `fill` to the end of values shorter than `width` characters.
+    PadEnd { width: usize, fill: char },
+
+    /// Adds a string to the start of each value.
+    Prefix(String),
+
+    /// Adds a string to the end of each value.
+    Suffix(String),
+
+    /// Replaces every occurrence of `from` with `to`.
+    Replace { from: String, to: String },
+}
+
+/// The most characters of a [`CharClass`] to change the case of when rendering a regex
+const MAX_MAPPED_CLASS: usize = 1 << 16;
+
+impl BuiltinTransform {
+    /// Applies the transform to `s`.
+    pub fn apply(&self, s: String) -> String {
+        use BuiltinTransform::*;
+        match self {
+            TrimLeadingZeros => match s.trim_start_matches('0') {
+                "" if !s.is_empty() => "0".into(),
+                trimmed => trimmed.into(),
+            },
+            Uppercase => s.to_uppercase(),
+            Lowercase => s.to_lowercase(),
+            TitleCase => {
+                let mut title = String::with_capacity(s.len());
+                let mut word_start = true;
+                for c in s.chars() {
+                    if word_start {
+                        title.extend(c.to_uppercase());
+                    } else {
+                        title.extend(c.to_lowercase());
+                    }
+                    word_start = c.is_whitespace();
+                }
+                title
+            }
+            Reverse => s.chars().rev().collect(),
+            PadStart { width, fill } => match width.checked_sub(s.chars().count()) {
+                Some(n) if n > 0 => std::iter::repeat_n(*fill, n).chain(s.chars()).collect(),
+                _ => s,
+            },
+            PadEnd { width, fill } => match width.checked_sub(s.chars().count()) {
+                Some(n) if n > 0 => s.chars().chain(std::iter::repeat_n(*fill, n)).collect(),
+                _ => s,
+            },
+            Prefix(prefix) => format!("{prefix}{s}"),
+            Suffix(suffix) => s + suffix,
+            Replace { from, to } => s.replace(from.as_str(), to),
+        }
+    }
+
+    /// Every string that this transform turns into `s`, or `None` if there may be infinitely or
+    /// unmanageably many of them.
+    pub(crate) fn preimages(&self, s: &str) -> Option<Vec<String>> {
+        use BuiltinTransform::*;
+        let preimages = match self {
+            Reverse => vec![s.chars().rev().collect()],
+            Prefix(prefix) => s
+                .strip_prefix(prefix.as_str())
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            Suffix(suffix) => s
+                .strip_suffix(suffix.as_str())
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            PadStart { width, fill } | PadEnd { width, fill } => {
+                let len = s.chars().count();
+                if len != *width {
+                    // Only values of at least `width` characters are left as they are
+                    return Some(if len > *width { vec![s.into()] } else { vec![] });
+                }
+
+                // A value of exactly `width` characters may also have been padded from any
+                // shorter one
+                let start = matches!(self, PadStart { .. });
+                let fills = if start {
+                    s.chars().take_while(|c| c == fill).count()
+                } else {
+                    s.chars().rev().take_while(|c| c == fill).count()
+                };
+                (0..=fills)
+                    .map(|n| {
+                        let n = n * fill.len_utf8();
+                        if start {
+                            s[n..].to_string()
+                        } else {
+                            s[..s.len() - n].to_string()
+                        }
+                    })
+                    .collect()
+            }
+            TrimLeadingZeros | Uppercase | Lowercase | TitleCase | Replace { .. } => return None,
+        };
+        Some(preimages)
+    }
+
+    /// A generator of exactly the values that this transform turns `g`'s values into, though not
+    /// in the same order, or `None` if they can't be described by rewriting `g`.
+    pub(crate) fn map_generator(&self, g: &Generator) -> Option<Generator> {
+        use BuiltinTransform::*;
+        match self {
+            Prefix(prefix) => Some(Generator::from(prefix.as_str()) + g.clone()),
+            Suffix(suffix) => Some(g.clone() + Generator::from(suffix.as_str())),
+            Reverse | Uppercase | Lowercase => self.rewrite(g),
+            TrimLeadingZeros | TitleCase | PadStart { .. } | PadEnd { .. } | Replace { .. } => None,
+        }
+    }
+
+    /// Rewrites `g` for a transform that changes each character, or the order of the
+    /// characters, independently of the rest of the value.
+    fn rewrite(&self, g: &Generator) -> Option<Generator> {
+        use Generator::*;
+        let each = |v: &[Generator]| {
+            v.iter()
+                .map(|a| self.rewrite(a))
+                .collect::<Option<Vec<_>>>()
+        };
+
+        let rewritten = match g {
+            IntRange(range) => self.rewrite(&range.to_generator())?,
+            // Rewriting its fields would lose track of the impossible dates they also match
+            DateTime(_) | Filter(_) => return None,
+            Automaton(automaton) => self.rewrite(&automaton.to_generator())?,
+            OneOf { v, is_optional } => OneOf {
+                v: each(v)?,
+                is_optional: *is_optional,
+            },
+            RepeatedN(a, n) => RepeatedN(Box::new(self.rewrite(a)?), *n),
+            RepeatedMN(a, m, n) => RepeatedMN(Box::new(self.rewrite(a)?), *m, *n),
+            Sequence(v) => {
+                let mut v = each(v)?;
+                if *self == BuiltinTransform::Reverse {
+                    v.reverse();
+                }
+                Sequence(v)
+            }
+            Transform {
+                inner,
+                transform_fn,
+            } => self.rewrite(&transform_fn.builtin()?.map_generator(inner)?)?,
+            Empty => Empty,
+            Str(s) if *self == BuiltinTransform::Reverse => Str(s.chars().rev().collect()),
+            Str(s) => Str(s
+                .chars()
+                .map(|c| self.change_case(c))
+                .collect::<Option<_>>()?),
+            _ if *self == BuiltinTransform::Reverse => g.clone(),
+            Char(c) => Char(self.change_case(*c)?),
+            CharClass(class) if class.len() <= MAX_MAPPED_CLASS => CharClass(
+                (0..class.len())
+                    .map(|i| self.change_case(class.get(i)?))
+                    .collect::<Option<_>>()?,
+            ),
+            CharClass(_) => return None,
+            AlphaLower | AlphaUpper | Digit | AlphaNumLower | AlphaNumUpper | HexUpper
+            | HexLower | Base64 | Base64Url | Base32 | Base58 | PrintableAscii | Punctuation
+            | Whitespace => {
+                let alphabet = g.alphabet().expect("built-in classes have alphabets");
+                let class: crate::CharClass = alphabet
+                    .iter()
+                    .map(|b| self.change_case(*b as char))
+                    .collect::<Option<_>>()?;
+
+                // Prefer a built-in class with the same characters, which renders more readably
+                let builtins = [
+                    AlphaLower,
+                    AlphaUpper,
+                    AlphaNumLower,
+                    AlphaNumUpper,
+                    HexLower,
+                    HexUpper,
+                ];
+                std::iter::once(g.clone())
+                    .chain(builtins)
+                    .find(|b| b.alphabet().map(class_of) == Some(class.clone()))
+                    .unwrap_or(CharClass(class))
+            }
+        };
+        Some(rewritten)
+    }
+
+    /// The case of `c` changed by this transform, unless that gives several characters or
+    /// depends on the surrounding characters.
+    fn change_case(&self, c: char) -> Option<char> {
+        let mut changed = match self {
+            BuiltinTransform::Uppercase => c.to_uppercase().collect::<Vec<_>>(),
+            // A lowercase sigma depends on whether it ends a word
+            BuiltinTransform::Lowercase if c != 'Σ' => c.to_lowercase().collect(),
+            _ => return None,
+        };
+        match changed.len() {
+            1 => changed.pop(),
+            _ => None,
+        }
+    }
+}
+
+fn class_of(alphabet: &[u8]) -> CharClass {
+    alphabet.iter().map(|b| *b as char).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{oneof, RegexOptions};
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn identity() {
+        let upper = TransformFn::new(|s: String| s.to_uppercase());
+        assert_eq!(upper, upper.clone());
+        assert_ne!(upper, TransformFn::new(|s: String| s.to_uppercase()));
+        assert_eq!(format!("{upper:?}"), "TransformFn(<unnamed>)");
+
+        let named = TransformFn::named("upper", |s: String| s.to_uppercase());
+        assert_eq!(
+            named,
+            TransformFn::named("upper", |s: String| s.to_ascii_uppercase())
+        );
+        assert_ne!(
+            named,
+            TransformFn::named("lower", |s: String| s.to_lowercase())
+        );
+        assert_ne!(named, upper.clone().with_name("other"));
+        assert_eq!(named, upper.clone().with_name("upper"));
+        assert_eq!(format!("{named:?}"), "TransformFn(\"upper\")");
+
+        let suffix = String::from("!");
+        let shared: Arc<dyn Fn(String) -> String + Send + Sync> = Arc::new(move |s| s + &suffix);
+        let exclaim = TransformFn::from(shared.clone());
+        assert_eq!(exclaim, TransformFn::from(shared));
+        assert_eq!(exclaim.apply("hi".into()), "hi!");
+
+        let builtin = TransformFn::from(BuiltinTransform::Uppercase);
+        assert_eq!(builtin, BuiltinTransform::Uppercase.into());
+        assert_ne!(builtin, upper);
+        assert_ne!(builtin, BuiltinTransform::Lowercase.into());
+        assert_eq!(format!("{builtin:?}"), "TransformFn(Uppercase)");
+
+        // A name doesn't make different kinds of transform equal
+        assert_ne!(builtin.clone().with_name("upper"), named);
+        assert_ne!(named, builtin.clone().with_name("upper"));
+        assert_eq!(
+            builtin.clone().with_name("upper"),
+            builtin.clone().with_name("upper")
+        );
+        let contextual = TransformFn::contextual(|s, _| s.to_uppercase()).with_name("upper");
+        assert_ne!(contextual, named);
+    }
+
+    #[test]
+    fn apply() {
+        use BuiltinTransform::*;
+        let cases = [
+            (TrimLeadingZeros, "00120", "120"),
+            (TrimLeadingZeros, "000", "0"),
+            (TrimLeadingZeros, "", ""),
+            (Uppercase, "straße", "STRASSE"),
+            (Lowercase, "ÀB", "àb"),
+            (TitleCase, "hELLO  wide\tworld", "Hello  Wide\tWorld"),
+            (Reverse, "añb", "bña"),
+            (
+                PadStart {
+                    width: 4,
+                    fill: '0',
+                },
+                "42",
+                "0042",
+            ),
+            (
+                PadStart {
+                    width: 2,
+                    fill: '0',
+                },
+                "123",
+                "123",
+            ),
+            (
+                PadEnd {
+                    width: 3,
+                    fill: '·',
+                },
+                "a",
+                "a··",
+            ),
+            (Prefix("x-".into()), "1", "x-1"),
+            (Suffix(".txt".into()), "a", "a.txt"),
+            (
+                Replace {
+                    from: "-".into(),
+                    to: "".into(),
+                },
+                "1-2-3",
+                "123",
+            ),
+        ];
+        for (t, s, expected) in cases {
+            assert_eq!(t.apply(s.into()), expected, "{t:?}");
+        }
+    }
+
+    #[test]
+    fn regex() {
+        use BuiltinTransform::*;
+        let g = oneof!("ab", "c") + Generator::AlphaLower * (0, 1) + Generator::class("x-zé");
+        let expected = [
+            (Prefix("(".into()), r"\((ab|c)[a-z]?[x-zé]"),
+            (Suffix("?".into()), r"(ab|c)[a-z]?[x-zé]\?"),
+            (Reverse, "[x-zé][a-z]?(ba|c)"),
+            (Uppercase, "(AB|C)[A-Z]?[X-ZÉ]"),
+        ];
+        for (t, regex) in expected {
+            let transformed = g.clone().transform(t.clone());
+            assert_eq!(transformed.try_regex().as_deref(), Ok(regex), "{t:?}");
+
+            // The rewritten generator has exactly the transformed values
+            let mapped = t.map_generator(&g).unwrap();
+            let values: BTreeSet<_> = transformed.generate_all().collect();
+            assert_eq!(values, mapped.generate_all().collect(), "{t:?}");
+        }
+
+        let g = (Generator::HexUpper + Generator::int_range(8..=12))
+            .transform(Lowercase)
+            .transform(Reverse);
+        assert_eq!(g.try_regex().unwrap(), r"([89]|[0-2]1)[\da-f]");
+
+        // Other transforms are still rendered inexactly
+        let g = (Generator::Digit * 3).transform(TrimLeadingZeros);
+        assert!(g.try_regex().is_err());
+        let options = RegexOptions {
+            allow_inexact: true,
+            ..Default::default()
+        };
+        assert_eq!(g.regex_with(&options).unwrap(), r"\d{3}");
+    }
+
+    #[test]
+    fn inverse() {
+        use BuiltinTransform::*;
+        let g = Generator::int_range(1..=120) + oneof!("", "0");
+        for t in [
+            Reverse,
+            Prefix("n".into()),
+            Suffix("0".into()),
+            PadStart {
+                width: 3,
+                fill: '0',
+            },
+            PadEnd {
+                width: 3,
+                fill: '0',
+            },
+        ] {
+            let transformed = g.clone().transform(t.clone());
+            for (i, s) in transformed.generate_all().enumerate() {
+                let indices = transformed.indices_of(&s);
+                assert!(
+                    indices.contains(&(i as u128)),
+                    "{t:?}: {s:?} didn't map to {i}"
+                );
+                for j in indices {
+                    assert_eq!(transformed.generate_one(j), s, "{t:?}");
+                }
+            }
+        }
+
+        let g = Generator::AlphaLower.transform(Uppercase);
+        assert_eq!(g.index_of("A"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let t = BuiltinTransform::PadStart {
+            width: 4,
+            fill: '0',
+        };
+        let json = serde_json::to_string(&t).unwrap();
+        assert_eq!(json, r#"{"PadStart":{"width":4,"fill":"0"}}"#);
+        assert_eq!(serde_json::from_str::<BuiltinTransform>(&json).unwrap(), t);
     }
 }
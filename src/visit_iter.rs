@@ -1,42 +1,83 @@
 use crate::Generator;
 
-/// Provides iterable access to the range of values represented by the [`Generator`]
+/// Provides iterable access to the range of values represented by the [`Generator`], yielding a
+/// [`Visit`] for each value rather than a [`String`].
 #[derive(Clone, Debug)]
 pub struct VisitIter<'a> {
+    /// The generator to be used
     c: &'a Generator,
+
+    /// The total number of values for `c`, equal to `c.len()`
     n: u128,
+
+    /// The current value of the iterator. The first value is 0, the last is `n-1`.
     i: u128,
 }
 
-impl<'a> VisitIter<'a> {
+/// A single value of a [`Generator`], whose component parts can be visited without building the
+/// whole [`String`].
+#[derive(Clone, Copy, Debug)]
+pub struct Visit<'a> {
+    c: &'a Generator,
+    i: u128,
+}
+
+impl<'a> Visit<'a> {
+    /// The index of this value, as would be passed to [`Generator::generate_one`].
+    pub fn index(&self) -> u128 {
+        self.i
+    }
+
+    /// Applies the callback `cb` for each of the component values, in order. The parts are the
+    /// same as those passed to [`Generator::visit_one`].
     pub fn visit<F>(&self, cb: F)
     where
         F: FnMut(&str),
     {
-        self.c.visit_one(self.i, cb);
+        self.visit_with(&mut String::new(), cb);
+    }
+
+    /// Like [`visit`](Self::visit), but uses `scratch` as working space for repeated parts.
+    ///
+    /// Reusing one `scratch` buffer when visiting many values avoids allocating for each of them;
+    /// only parts from [`Generator::Transform`] still allocate.
+    pub fn visit_with<F>(&self, scratch: &mut String, mut cb: F)
+    where
+        F: FnMut(&str),
+    {
+        self.c
+            .visit_exact_inner(&mut self.i.clone(), scratch, &mut cb);
     }
 }
 
 impl<'a> Iterator for VisitIter<'a> {
-    type Item = Self;
+    type Item = Visit<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.i == self.n {
             None
         } else {
-            let result = self.clone();
             self.i += 1;
-            Some(result)
+            Some(Visit {
+                c: self.c,
+                i: self.i - 1,
+            })
         }
     }
 }
 
 #[cfg(feature = "with_rand")]
 impl<'a> VisitIter<'a> {
-    /// Generates a random value in the [`Generator`]'s domain
-    pub fn random(&self) -> String {
-        let num = rand::random::<u128>() % self.n;
-        self.c.generate_one(num)
+    /// Selects a random value in the [`Generator`]'s domain
+    pub fn random(&self) -> Visit<'a> {
+        self.random_with(&mut rand::thread_rng())
+    }
+
+    /// Selects a random value in the [`Generator`]'s domain, using `rng` as the source of
+    /// randomness
+    pub fn random_with<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Visit<'a> {
+        let num = crate::random::index_below(rng, self.n);
+        Visit { c: self.c, i: num }
     }
 }
 
@@ -49,3 +90,43 @@ impl<'a> From<&'a Generator> for VisitIter<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{gen, oneof, Generator};
+
+    #[test]
+    fn matches_generate_all() {
+        let g = oneof!("N", "E", "S", "W").optional()
+            + ' '
+            + Generator::Digit * (1, 2)
+            + (gen!('-') + oneof!('A', 'B')).optional() * 2
+            + Generator::HexLower.transform(|s: String| s.to_uppercase());
+        assert_eq!(g.len(), 5 * 110 * 9 * 16);
+
+        let mut scratch = String::new();
+        let mut value = String::new();
+        let mut count = 0;
+        for (visit, generated) in g.visit_all().zip(g.generate_all()).step_by(31) {
+            value.clear();
+            visit.visit_with(&mut scratch, |part| value.push_str(part));
+            assert_eq!(value, generated);
+
+            let mut parts = vec![];
+            visit.visit(|part| parts.push(part.to_string()));
+            let mut expected = vec![];
+            g.visit_one(visit.index(), |part| expected.push(part));
+            assert_eq!(parts, expected);
+
+            count += 1;
+        }
+        assert_eq!(count, g.len().div_ceil(31));
+    }
+
+    #[test]
+    fn indices() {
+        let g = oneof!("foo", "bar", "baz");
+        let indices: Vec<_> = g.visit_all().map(|v| v.index()).collect();
+        assert_eq!(indices, [0, 1, 2]);
+    }
+}